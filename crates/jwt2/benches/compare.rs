@@ -0,0 +1,62 @@
+//! Compares `jwt2` against the `jsonwebtoken` crate, using HS256 since both support it with the
+//! same backing implementation.
+//! ```sh
+//! cargo bench --bench compare
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::{json, Value};
+
+const SECRET: &[u8] = b"your-256-bit-secret";
+
+fn claims() -> Value {
+    json!({ "sub": "1234567890", "name": "John Doe", "iat": 1516239022 })
+}
+
+fn sign(c: &mut Criterion) {
+    let claims = claims();
+    let mut group = c.benchmark_group("compare sign HS256");
+
+    let hs256 = jwt2::sign::HS256::new(SECRET).unwrap();
+    let data = jwt2::JwtData {
+        header: jwt2::Header::recommended(&hs256),
+        claims: &claims,
+    };
+    group.bench_function("jwt2", |b| {
+        b.iter(|| black_box(&data).sign_with(&hs256).unwrap())
+    });
+
+    let header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+    let key = jsonwebtoken::EncodingKey::from_secret(SECRET);
+    group.bench_function("jsonwebtoken", |b| {
+        b.iter(|| jsonwebtoken::encode(&header, black_box(&claims), &key).unwrap())
+    });
+    group.finish();
+}
+
+fn verify(c: &mut Criterion) {
+    let hs256 = jwt2::sign::HS256::new(SECRET).unwrap();
+    let token =
+        jwt2::jwt::sign_value(&jwt2::Header::recommended(&hs256), &claims(), &hs256).unwrap();
+    let mut group = c.benchmark_group("compare verify HS256");
+
+    group.bench_function("jwt2", |b| {
+        b.iter(|| {
+            let jwt = jwt2::jwt::RawJwt::decode(black_box(&token)).unwrap();
+            assert!(jwt.verify_signature(&hs256));
+            jwt.parse::<Value>().unwrap()
+        })
+    });
+
+    let key = jsonwebtoken::DecodingKey::from_secret(SECRET);
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
+    validation.required_spec_claims.clear();
+    validation.validate_exp = false;
+    group.bench_function("jsonwebtoken", |b| {
+        b.iter(|| jsonwebtoken::decode::<Value>(black_box(&token), &key, &validation).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sign, verify);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+//! Compare the JSON backends by running this with and without the `simd-json` feature:
+//! ```sh
+//! cargo bench --bench decode
+//! cargo bench --bench decode --features simd-json
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use jwt2::repr;
+use serde_json::{json, Value};
+
+fn decode_value(c: &mut Criterion) {
+    let small = repr::encode_value_as_base64url(&json!({
+        "sub": "1234567890",
+        "name": "John Doe",
+        "iat": 1516239022
+    }))
+    .unwrap();
+    let large = repr::encode_value_as_base64url(&json!({
+        "sub": "1234567890",
+        "roles": (0..256).map(|i| format!("role-{i}")).collect::<Vec<_>>(),
+        "groups": (0..64).map(|i| json!({ "id": i, "name": format!("group-{i}") })).collect::<Vec<_>>(),
+    }))
+    .unwrap();
+
+    let mut group = c.benchmark_group("decode value");
+    for (name, encoded) in [("small", &small), ("large", &large)] {
+        group.throughput(Throughput::Bytes(encoded.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| repr::decode_value_from_base64url::<Value>(black_box(encoded)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode_value);
+criterion_main!(benches);
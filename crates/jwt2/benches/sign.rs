@@ -0,0 +1,91 @@
+//! Signing and verification across algorithms. RSA and ECDSA are only included when their
+//! features are enabled:
+//! ```sh
+//! cargo bench --bench sign --features rsa-pkcs1,ecdsa
+//! ```
+//! To compare against a baseline (e.g. before and after a change), use criterion's
+//! `--save-baseline <name>` and `--baseline <name>` options.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use jwt2::jwt::RawJwt;
+use jwt2::sign::HS256;
+use jwt2::{Header, JwsSigner, JwsVerifier, JwtData};
+use serde_json::json;
+
+/// A signer and verifier for the same algorithm, along with the algorithm's name.
+type Pair = (&'static str, Box<dyn JwsSigner>, Box<dyn JwsVerifier>);
+
+/// Pairs of signers and verifiers for every enabled algorithm.
+fn algorithms() -> Vec<Pair> {
+    #[allow(unused_mut)]
+    let mut algorithms: Vec<Pair> = vec![(
+        "HS256",
+        Box::new(HS256::new(b"your-256-bit-secret").unwrap()),
+        Box::new(HS256::new(b"your-256-bit-secret").unwrap()),
+    )];
+    #[cfg(feature = "rsa-pkcs1")]
+    {
+        let key = rsa::RsaPrivateKey::new(&mut rand_core::OsRng, 2048).unwrap();
+        let rs256 = jwt2::sign::RS256::from(rsa::pkcs1v15::SigningKey::new(key));
+        algorithms.push(("RS256", Box::new(rs256.clone()), Box::new(rs256.public())));
+    }
+    #[cfg(feature = "ecdsa")]
+    {
+        let es256 =
+            jwt2::sign::ecdsa::ES256::from(p256::ecdsa::SigningKey::random(&mut rand_core::OsRng));
+        algorithms.push(("ES256", Box::new(es256.clone()), Box::new(es256.public())));
+    }
+    algorithms
+}
+
+fn sign_and_verify(c: &mut Criterion) {
+    let claims = json!({ "sub": "1234567890", "name": "John Doe", "iat": 1516239022 });
+    for (name, signer, verifier) in algorithms() {
+        let data = JwtData {
+            header: Header::recommended(&*signer),
+            claims: &claims,
+        };
+        let token = data.sign_with(&*signer).unwrap();
+
+        let mut group = c.benchmark_group(name);
+        group.bench_function("sign", |b| {
+            b.iter(|| black_box(&data).sign_with(&*signer).unwrap())
+        });
+        group.bench_function("verify", |b| {
+            b.iter(|| {
+                RawJwt::decode(black_box(&token))
+                    .unwrap()
+                    .verify_signature(&*verifier)
+            })
+        });
+        group.finish();
+    }
+}
+
+fn sign_with(c: &mut Criterion) {
+    let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+    let small = JwtData {
+        header: Header::recommended(&hs256),
+        claims: json!({ "sub": "1234567890", "name": "John Doe", "iat": 1516239022 }),
+    };
+    let large = JwtData {
+        header: Header::recommended(&hs256),
+        claims: json!({
+            "sub": "1234567890",
+            "roles": (0..256).map(|i| format!("role-{i}")).collect::<Vec<_>>(),
+        }),
+    };
+
+    let mut group = c.benchmark_group("sign_with");
+    for (name, data) in [("small", &small), ("large", &large)] {
+        let len = data.sign_with(&hs256).unwrap().len();
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(data).sign_with(&hs256).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sign_with, sign_and_verify);
+criterion_main!(benches);
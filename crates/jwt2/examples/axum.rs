@@ -0,0 +1,57 @@
+//! A small axum service with a route that requires a valid HS256-signed token.
+//!
+//! Run it with `cargo run --example axum --features axum,hmac-sha2`, then call it using the
+//! token it prints:
+//! ```sh
+//! curl -H "Authorization: Bearer <token>" http://127.0.0.1:3000/whoami
+//! ```
+
+use axum::routing::get;
+use axum::Router;
+use jwt2::axum::{Claims, JwtState};
+use jwt2::sign::HS256;
+use jwt2::{Header, JwtData};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize)]
+struct User {
+    sub: String,
+    name: String,
+    exp: u64,
+}
+
+async fn whoami(Claims(user): Claims<User>) -> String {
+    format!("Hello, {} ({})!", user.name, user.sub)
+}
+
+#[tokio::main]
+async fn main() {
+    let hs256 = HS256::new(b"your-256-bit-secret").expect("Could not construct HS256");
+
+    let token = JwtData {
+        header: Header::recommended(&hs256),
+        claims: User {
+            sub: "1234567890".to_string(),
+            name: "John Doe".to_string(),
+            // Valid for an hour.
+            exp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("The clock is before 1970")
+                .as_secs()
+                + 60 * 60,
+        },
+    }
+    .sign_with(&hs256)
+    .expect("Could not sign token");
+    println!("Token: {token}");
+
+    let app = Router::new()
+        .route("/whoami", get(whoami))
+        .with_state(JwtState::new(hs256));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .expect("Could not bind listener");
+    axum::serve(listener, app).await.expect("Server error");
+}
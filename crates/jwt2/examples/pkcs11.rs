@@ -0,0 +1,137 @@
+//! Signing tokens with a key that never leaves a PKCS#11 module (an HSM, smart card, etc.).
+//!
+//! Such keys can't be exported, so rather than constructing one of `jwt2`'s own signers, you
+//! implement [`JwsSigner`] yourself and let the module do the actual signing.
+//! To keep this runnable anywhere, the module is mocked using an in-memory key; with a real
+//! module (e.g. using the `cryptoki` crate), [`Pkcs11Session::sign`] would call `C_SignInit`
+//! and `C_Sign`.
+//!
+//! Run it with `cargo run --example pkcs11 --features ecdsa`.
+
+use jwt2::jwt::RawJwt;
+use jwt2::sign::ecdsa::ES256;
+use jwt2::sign::SignError;
+use jwt2::{Algorithm, Header, JwsSigner, JwtData, RecommendHeaderParams, SigningAlgorithm};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The parts of a PKCS#11 session that signing needs.
+trait Pkcs11Session {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Signs `data` with the private key `key`.
+    ///
+    /// For ECDSA keys, this should use a mechanism that hashes the data too, i.e.
+    /// `CKM_ECDSA_SHA256` for ES256. Conveniently, PKCS#11 returns ECDSA signatures as `r || s`,
+    /// which is exactly the format JWS uses, so they don't need converting.
+    fn sign(&self, key: u64, data: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// A [`JwsSigner`] for a key stored in a PKCS#11 module.
+struct Pkcs11Signer<Session> {
+    session: Session,
+    key: u64,
+    // The module doesn't know about JWS, so the algorithm has to be specified separately.
+    // It must match the key and mechanism, otherwise tokens will fail to verify.
+    algorithm: SigningAlgorithm,
+    key_id: Option<String>,
+}
+
+impl<Session> RecommendHeaderParams for Pkcs11Signer<Session> {
+    fn alg(&self) -> Algorithm {
+        Algorithm::Signing(self.algorithm)
+    }
+    fn kid(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+}
+
+impl<Session> JwsSigner for Pkcs11Signer<Session>
+where
+    Session: Pkcs11Session,
+{
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        self.try_sign_to(data, out)
+            .expect("The PKCS#11 module could not sign")
+    }
+    // The module can fail (e.g. when it's unplugged or the session has expired), which is what
+    // `try_sign_to` is for. `JwtData::sign_with` and the other ways of creating tokens use it and
+    // return the error; only calling `sign` or `sign_to` directly panics.
+    fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+        let signature = self
+            .session
+            .sign(self.key, data)
+            .map_err(|e| SignError::Other(Box::new(e)))?;
+        out.extend_from_slice(&signature);
+        Ok(())
+    }
+    fn signature_len(&self) -> Option<usize> {
+        // ECDSA signatures are `r || s`, each as long as the curve's field elements. Everything
+        // else depends on the key, and this is only a hint anyway.
+        match self.algorithm {
+            SigningAlgorithm::ES256 => Some(64),
+            SigningAlgorithm::ES384 => Some(96),
+            _ => None,
+        }
+    }
+}
+
+/// A stand-in for a PKCS#11 module holding a single ES256 key.
+struct MockModule {
+    key: ES256,
+    logged_in: AtomicBool,
+}
+
+#[derive(Debug)]
+struct NotLoggedIn;
+
+impl std::fmt::Display for NotLoggedIn {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("CKR_USER_NOT_LOGGED_IN")
+    }
+}
+
+impl std::error::Error for NotLoggedIn {}
+
+impl Pkcs11Session for &MockModule {
+    type Error = NotLoggedIn;
+
+    fn sign(&self, _key: u64, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        if !self.logged_in.load(Ordering::Relaxed) {
+            return Err(NotLoggedIn);
+        }
+        Ok(self.key.sign(data))
+    }
+}
+
+fn main() {
+    let secret = p256::ecdsa::SigningKey::from_slice(&[7; 32]).expect("Invalid key");
+    let module = MockModule {
+        key: ES256::from(secret),
+        logged_in: AtomicBool::new(true),
+    };
+    // In reality, only the public key can be read from the module.
+    let public = module.key.public();
+
+    let signer = Pkcs11Signer {
+        session: &module,
+        key: 1,
+        algorithm: SigningAlgorithm::ES256,
+        key_id: Some("hsm-key-1".to_string()),
+    };
+    let data = JwtData {
+        header: Header::recommended(&signer),
+        claims: json!({ "sub": "1234567890" }),
+    };
+    let token = data.sign_with(&signer).expect("Could not sign");
+    println!("{token}");
+
+    let jwt = RawJwt::decode(&token).expect("Could not decode");
+    assert_eq!(jwt.header.key_id.as_deref(), Some("hsm-key-1"));
+    assert!(jwt.verify_signature(&public));
+
+    // Failures in the module are reported instead of panicking.
+    module.logged_in.store(false, Ordering::Relaxed);
+    let error = data.sign_with(&signer).unwrap_err();
+    println!("After logging out: {error}");
+}
@@ -0,0 +1,191 @@
+//! Integration with [`axum`].
+//!
+//! The [`Claims`] extractor reads a bearer token from the `Authorization` header, verifies it
+//! using the [`JwtState`] in your application state, and deserialises its claims.
+//! Requests without a valid token are rejected with `401 Unauthorized`.
+//!
+//! ```
+//! # #[cfg(feature = "hmac-sha2")] {
+//! use axum::{routing::get, Router};
+//! use jwt2::axum::{Claims, JwtState};
+//! use jwt2::sign::HS256;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct User {
+//!     sub: String,
+//! }
+//!
+//! async fn whoami(Claims(user): Claims<User>) -> String {
+//!     user.sub
+//! }
+//!
+//! let hs256 = HS256::new(b"your-256-bit-secret").expect("Could not construct HS256");
+//! let app: Router = Router::new()
+//!     .route("/whoami", get(whoami))
+//!     .with_state(JwtState::new(hs256));
+//! # }
+//! ```
+//!
+//! If your application has state of its own, implement [`FromRef`] for [`JwtState`] on it.
+
+use crate::jwt::{self, JwtDecodeError, VerifyError};
+use crate::{JwsVerifier, ValidationOptions};
+use ::axum::extract::{FromRef, FromRequestParts};
+use ::axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use ::axum::http::request::Parts;
+use ::axum::http::StatusCode;
+use ::axum::response::{IntoResponse, Response};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// What [`Claims`] verifies tokens with.
+#[derive(Clone)]
+pub struct JwtState {
+    pub verifier: Arc<dyn JwsVerifier + Send + Sync>,
+    pub options: ValidationOptions,
+}
+
+impl JwtState {
+    /// Creates a state that verifies tokens using `verifier` and the default
+    /// [`ValidationOptions`].
+    pub fn new<Verifier>(verifier: Verifier) -> Self
+    where
+        Verifier: JwsVerifier + Send + Sync + 'static,
+    {
+        Self::with_options(verifier, ValidationOptions::default())
+    }
+
+    /// Creates a state that verifies tokens using `verifier` and validates them with `options`.
+    pub fn with_options<Verifier>(verifier: Verifier, options: ValidationOptions) -> Self
+    where
+        Verifier: JwsVerifier + Send + Sync + 'static,
+    {
+        Self {
+            verifier: Arc::new(verifier),
+            options,
+        }
+    }
+}
+
+/// Extracts the claims of a verified bearer token, deserialised into `T`.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub struct Claims<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Claims<T>
+where
+    S: Send + Sync,
+    JwtState: FromRef<S>,
+    T: DeserializeOwned,
+{
+    type Rejection = ClaimsRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = JwtState::from_ref(state);
+        let value = parts
+            .headers
+            .get(AUTHORIZATION)
+            .ok_or(ClaimsRejection::MissingToken)?
+            .to_str()
+            .map_err(|_| ClaimsRejection::MissingToken)?;
+
+        let jwt = jwt::from_authorization_header(value)?;
+        jwt.verify(&*state.verifier, &state.options)?;
+        Ok(Self(jwt.parse::<T>()?.claims))
+    }
+}
+
+/// Why [`Claims`] rejected a request.
+///
+/// This always responds with `401 Unauthorized`; the details are only for logging.
+#[derive(Debug, thiserror::Error)]
+pub enum ClaimsRejection {
+    #[error("the request has no bearer token")]
+    MissingToken,
+    #[error("could not decode the token: {0}")]
+    Decode(#[from] JwtDecodeError),
+    #[error("could not verify the token: {0}")]
+    Verify(#[from] VerifyError),
+}
+
+impl IntoResponse for ClaimsRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, "Bearer")]).into_response()
+    }
+}
+
+#[cfg(all(test, feature = "hmac-sha2"))]
+mod tests {
+    use super::*;
+    use crate::jwt::Jwt;
+    use crate::sign::HS256;
+    use ::axum::body::Body;
+    use ::axum::http::Request;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use tower::ServiceExt;
+
+    #[derive(serde::Deserialize)]
+    struct User {
+        sub: String,
+    }
+
+    fn app(secret: &[u8]) -> Router {
+        async fn whoami(Claims(user): Claims<User>) -> String {
+            user.sub
+        }
+
+        let hs256 = HS256::new(secret).unwrap();
+        Router::new()
+            .route("/whoami", get(whoami))
+            .with_state(JwtState::new(hs256))
+    }
+
+    fn token(secret: &[u8]) -> String {
+        let hs256 = HS256::new(secret).unwrap();
+        Jwt::builder()
+            .claim("sub", "1234567890")
+            .claim("exp", u64::MAX)
+            .sign(&hs256)
+            .unwrap()
+    }
+
+    async fn request(authorization: Option<String>) -> Response {
+        let mut request = Request::get("/whoami");
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        app(b"your-256-bit-secret")
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn valid_token() {
+        let token = token(b"your-256-bit-secret");
+        let response = request(Some(format!("Bearer {token}"))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = ::axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"1234567890");
+    }
+
+    #[tokio::test]
+    async fn rejected_tokens() {
+        let wrong_key = token(b"some-other-256-bit-secret");
+        for authorization in [
+            None,
+            Some("Basic dXNlcjpwYXNzd29yZA==".to_string()),
+            Some("Bearer not.a.token".to_string()),
+            Some(format!("Bearer {wrong_key}")),
+        ] {
+            let response = request(authorization).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+        }
+    }
+}
@@ -1,6 +1,18 @@
 use crate::sign;
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// A JOSE header.
+///
+/// Serialisation is deterministic: the known parameters come first, in the order they're
+/// declared here (`alg`, `kid`, `x5u`, `typ`, `cty`, `crit`, `b64`), followed by
+/// [`Header::extra`] sorted by name. The order of the original JSON isn't kept, so if you need
+/// to reproduce a header exactly, keep its encoded form around
+/// (e.g. [`RawJwt::protected_header_b64`](crate::jwt::RawJwt::protected_header_b64)).
+///
+/// New header parameters get added as fields, so this can't be constructed with a struct
+/// literal outside of `jwt2`. Use [`Header::new`] or [`Header::recommended`] and set the fields
+/// you need instead.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
 pub struct Header {
     /// The algorithm that this object is/will be signed with.
     /// Corresponds to the `alg` header parameter.
@@ -13,36 +25,86 @@ pub struct Header {
     /// Corresponds to the `kid` header parameter.
     ///
     /// See [section 4.1.4 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.4).
-    #[serde(rename = "kid")]
+    #[serde(rename = "kid", skip_serializing_if = "Option::is_none")]
     pub key_id: Option<String>,
 
+    /// A URL pointing to the X.509 certificate (chain) for the key used to sign this object.
+    /// Corresponds to the `x5u` header parameter.
+    ///
+    /// `jwt2` never fetches this by itself; see [`crate::jwt::x5u`] for how to verify tokens
+    /// using it.
+    ///
+    /// See [section 4.1.5 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x5u: Option<String>,
+
     /// The type of the object that is encoded with this header.
     /// Corresponds to the `typ` header parameter.
     ///
     /// See [section 4.1.9 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.9).
-    #[serde(rename = "typ")]
+    #[serde(rename = "typ", skip_serializing_if = "Option::is_none")]
     pub obj_type: Option<String>,
 
+    /// The type of the payload, e.g. `JWT` for nested JWTs.
+    /// Corresponds to the `cty` header parameter.
+    ///
+    /// See [section 4.1.10 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.10).
+    #[serde(rename = "cty", skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
     /// A list of parameters, i.e. field names, that the JWS implementation (i.e. `jwt2`) is
     /// required to process.
     /// Corresponds to the `crit` header parameter.
     ///
-    /// This is a field that `jwt2` doesn't really support yet, but it is left in for the sake of
-    /// being standards-compliant.
+    /// The only extension `jwt2` understands is [`Header::b64`].
     ///
-    /// To validate this, use the [`Header::required_extensions`] function.
+    /// To validate this, use the [`Header::supports_required_extensions`] function.
     ///
     /// See [section 4.1.11 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.11).
-    #[serde(rename = "crit")]
+    #[serde(rename = "crit", skip_serializing_if = "Option::is_none")]
     pub required_extensions: Option<Vec<String>>,
+
+    /// Whether the payload is base64url-encoded.
+    /// Corresponds to the `b64` header parameter.
+    ///
+    /// If this is `Some(false)`, the payload is included in the JWS as-is, and `b64` must also be
+    /// listed in [`Header::required_extensions`].
+    /// Absence means the same as `Some(true)`.
+    ///
+    /// See [section 3 of RFC 7797](https://www.rfc-editor.org/rfc/rfc7797.html#section-3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b64: Option<bool>,
+
+    /// Any other header parameters, e.g. those of extensions `jwt2` doesn't know about.
+    ///
+    /// These are what a [`CritHandler`] gets to inspect.
+    ///
+    /// They are always serialised sorted by name, even if `serde_json`'s `preserve_order` feature
+    /// is enabled.
+    #[serde(flatten, serialize_with = "serialize_sorted")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+fn serialize_sorted<S>(
+    map: &serde_json::Map<String, serde_json::Value>,
+    ser: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    ser.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
 }
 impl Header {
     pub fn new(algorithm: Algorithm) -> Self {
         Self {
             algorithm,
             key_id: None,
+            x5u: None,
             obj_type: None,
+            content_type: None,
             required_extensions: None,
+            b64: None,
+            extra: serde_json::Map::new(),
         }
     }
 
@@ -53,34 +115,147 @@ impl Header {
         Self {
             algorithm: recommender.alg(),
             key_id: recommender.kid().map(str::to_string),
-            obj_type: None,
+            x5u: None,
+            obj_type: recommender.typ().map(str::to_string),
+            content_type: recommender.cty().map(str::to_string),
             required_extensions: None,
+            b64: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Parses a header from JSON like its [`Deserialize`](serde::Deserialize) implementation,
+    /// but also accepts a `kid` that is a number or boolean, converting it to a string.
+    ///
+    /// **This is not strict.** `kid` must be a string according to RFC 7515, so only use this
+    /// when you have to deal with non-compliant issuers.
+    pub fn from_json_lenient(json: &[u8]) -> Result<Self, serde_json::Error> {
+        let mut value: serde_json::Value = serde_json::from_slice(json)?;
+        if let Some(kid) = value.get_mut("kid") {
+            if kid.is_number() || kid.is_boolean() {
+                *kid = kid.to_string().into();
+            }
+        }
+        serde_json::from_value(value)
+    }
+
+    /// Returns a copy of this header with [`Header::algorithm`] replaced.
+    ///
+    /// Together with [`Header::with_key_id`], this is handy for re-signing a token with a
+    /// different key.
+    pub fn with_algorithm(&self, algorithm: Algorithm) -> Self {
+        Self {
+            algorithm,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this header with [`Header::key_id`] replaced.
+    pub fn with_key_id(&self, key_id: impl Into<String>) -> Self {
+        Self {
+            key_id: Some(key_id.into()),
+            ..self.clone()
         }
     }
 
+    /// The header parameters that `jwt2` understands as extensions, i.e. that may be listed in
+    /// [`Header::required_extensions`].
+    pub const SUPPORTED_EXTENSIONS: &'static [&'static str] = &["b64"];
+
     /// Checks if this library supports the required extensions.
     ///
-    /// # Implementation details
-    /// For now, this will return true if [`Self::required_extensions`] is `Some`.
-    ///
-    /// This is standards-compliant and "correct" behaviour since if it returns `true`:
-    /// 1. the array is empty, which is not standards-compliant:
-    ///    > Producers MUST NOT use the empty list `[]` as the `crit` value.
-    /// 2. the array contains headers which are technically supported by `jwt2`.
-    ///    At the moment, `jwt2` only supports header parameters that are specified by
-    ///    [the JWS RFC](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1)
-    ///    or those specified by [the JWA RFC](https://www.rfc-editor.org/rfc/rfc7518.html).
-    ///    > Recipients MAY consider the JWS to be invalid if the critical list contains
-    ///    > any Header Parameter names defined by this specification or JWA for use with JWS
-    ///    > or if any other constraints on its use are violated.
-    /// 3. the array specifies any parameter that `jwt2` does not recognise,
-    ///    being the genuinely correct case.
-    ///    > If any of the listed extension Header Parameters are not understood
-    ///    > and supported by the recipient, then the JWS is invalid.
-    ///
-    /// In the future, however, this function should properly check if the parameters are handled.
+    /// This returns `false` if:
+    /// - the list of required extensions is empty, which is not allowed:
+    ///   > Producers MUST NOT use the empty list `[]` as the `crit` value.
+    /// - any listed extension isn't in [`Header::SUPPORTED_EXTENSIONS`] or isn't present.
+    ///   > If any of the listed extension Header Parameters are not understood
+    ///   > and supported by the recipient, then the JWS is invalid.
+    ///
+    ///   Note that this includes the parameters defined by the JWS and JWA RFCs themselves,
+    ///   which the RFC allows rejecting.
+    /// - [`Header::b64`] is `false` but not listed, as required by
+    ///   [section 6 of RFC 7797](https://www.rfc-editor.org/rfc/rfc7797.html#section-6).
+    ///
+    /// To also accept extensions that you process yourself, see
+    /// [`Header::supports_required_extensions_with`].
+    ///
+    /// ```
+    /// use jwt2::{Algorithm, Header};
+    ///
+    /// let mut header = Header::new(Algorithm::None);
+    /// assert!(header.supports_required_extensions());
+    ///
+    /// header.b64 = Some(false);
+    /// assert!(!header.supports_required_extensions());
+    /// header.required_extensions = Some(vec!["b64".to_string()]);
+    /// assert!(header.supports_required_extensions());
+    /// ```
     pub fn supports_required_extensions(&self) -> bool {
-        self.required_extensions.is_some()
+        self.supports_required_extensions_with(None)
+    }
+
+    /// Like [`Header::supports_required_extensions`], but also accepts extensions that `handler`
+    /// [understands](CritHandler::understands) and [validates](CritHandler::validate).
+    ///
+    /// The extension parameter must be present in [`Header::extra`] for it to be accepted.
+    pub fn supports_required_extensions_with(&self, handler: Option<&dyn CritHandler>) -> bool {
+        let Some(ref required) = self.required_extensions else {
+            return self.b64 != Some(false);
+        };
+        if required.is_empty() {
+            return false;
+        }
+
+        let is_present = |name: &str| match name {
+            "b64" => self.b64.is_some(),
+            _ => false,
+        };
+        let is_handled = |name: &str| match handler {
+            Some(handler) => {
+                self.extra.contains_key(name)
+                    && handler.understands(name)
+                    && handler.validate(name, self)
+            }
+            None => false,
+        };
+        let all_supported = required.iter().all(|name| {
+            (Self::SUPPORTED_EXTENSIONS.contains(&name.as_str()) && is_present(name))
+                || is_handled(name)
+        });
+        all_supported && (self.b64 != Some(false) || required.iter().any(|name| name == "b64"))
+    }
+
+    /// Whether the payload is base64url-encoded, i.e. whether [`Header::b64`] isn't `false`.
+    pub fn is_payload_encoded(&self) -> bool {
+        self.b64.unwrap_or(true)
+    }
+
+    /// Whether [`Header::obj_type`] is the media type `expected`.
+    ///
+    /// As recommended by [section 4.1.9 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.9),
+    /// the comparison is case-insensitive and the `application/` prefix is optional, so e.g.
+    /// `at+jwt` matches `application/AT+JWT`. A missing `typ` never matches.
+    pub fn has_type(&self, expected: &str) -> bool {
+        fn normalise(media_type: &str) -> &str {
+            match media_type.get(..12) {
+                Some(prefix) if prefix.eq_ignore_ascii_case("application/") => &media_type[12..],
+                _ => media_type,
+            }
+        }
+        self.obj_type
+            .as_deref()
+            .is_some_and(|typ| normalise(typ).eq_ignore_ascii_case(normalise(expected)))
+    }
+}
+
+/// Displays the header as compact JSON, i.e. as it would be encoded in a JWT.
+impl core::fmt::Display for Header {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match serde_json::to_string(self) {
+            Ok(json) => f.write_str(&json),
+            // This shouldn't be able to happen, but Display is no place to report errors.
+            Err(_) => core::fmt::Debug::fmt(self, f),
+        }
     }
 }
 
@@ -114,6 +289,41 @@ impl core::fmt::Display for Algorithm {
     }
 }
 
+/// Processes header parameters listed in [`Header::required_extensions`] that `jwt2` doesn't
+/// understand by itself.
+///
+/// Verifiers expose their handler through [`ValidateHeaderParams::crit_handler`];
+/// see [`crate::util::WithCritHandler`] for attaching one to an existing verifier.
+///
+/// ```
+/// use jwt2::{Algorithm, CritHandler, Header};
+///
+/// /// Only accepts transactions with a numeric ID.
+/// struct TxnHandler;
+/// impl CritHandler for TxnHandler {
+///     fn understands(&self, name: &str) -> bool {
+///         name == "txn"
+///     }
+///     fn validate(&self, _name: &str, header: &Header) -> bool {
+///         header.extra["txn"].is_u64()
+///     }
+/// }
+///
+/// let mut header = Header::new(Algorithm::None);
+/// header.required_extensions = Some(vec!["txn".to_string()]);
+/// header.extra.insert("txn".to_string(), 42.into());
+/// assert!(!header.supports_required_extensions());
+/// assert!(header.supports_required_extensions_with(Some(&TxnHandler)));
+/// ```
+pub trait CritHandler {
+    /// Whether this handler knows how to process the extension parameter `name`.
+    fn understands(&self, name: &str) -> bool;
+    /// Checks the value of the extension parameter `name` in `header`.
+    ///
+    /// This is only called for extensions this handler [understands](CritHandler::understands).
+    fn validate(&self, name: &str, header: &Header) -> bool;
+}
+
 /// Something that can recommend header parameters. Useful with [`sign::JwsSigner`].
 pub trait RecommendHeaderParams {
     /// Recommends an algorithm.
@@ -122,19 +332,87 @@ pub trait RecommendHeaderParams {
     fn kid(&self) -> Option<&str> {
         None
     }
+    /// Recommends a `typ`. See [`crate::util::WithHeaderDefaults`].
+    fn typ(&self) -> Option<&str> {
+        None
+    }
+    /// Recommends a `cty`. See [`crate::util::WithHeaderDefaults`].
+    fn cty(&self) -> Option<&str> {
+        None
+    }
 }
 /// Indicates that something can validate header parameters. Useful with [`sign::JwsVerifier`].
+///
+/// Most implementations only need to implement [`ValidateHeaderParams::supports_algorithm`];
+/// the default [`ValidateHeaderParams::validate_header`] is built on top of it.
+/// Even verifiers that override `validate_header` have to say which algorithms they support,
+/// since tokens with any other `alg` are rejected before `validate_header` is called.
 pub trait ValidateHeaderParams {
     /// Check that the header is supported by this verifier.
-    fn validate_header(&self, header: &Header) -> bool;
+    ///
+    /// By default, this rejects the `none` algorithm and accepts any signing algorithm that
+    /// [`ValidateHeaderParams::supports_algorithm`] accepts.
+    fn validate_header(&self, header: &Header) -> bool {
+        match header.algorithm {
+            Algorithm::None => false,
+            Algorithm::Signing(alg) => self.supports_algorithm(alg),
+        }
+    }
+
+    /// Checks whether this verifier can verify signatures made with `alg`.
+    ///
+    /// Keys that can be used with several algorithms (e.g. an RSA key, which works for both
+    /// `RS256` and `PS256`) may accept more than one.
+    /// Tokens with an `alg` this rejects fail with
+    /// [`VerifyError::UnsupportedAlgorithm`](crate::jwt::VerifyError::UnsupportedAlgorithm).
+    fn supports_algorithm(&self, alg: sign::SigningAlgorithm) -> bool;
+
+    /// The ID of the key this verifier uses, if it has one. See [`crate::util::WithKeyId`].
+    fn key_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// The handler for critical extensions `jwt2` doesn't understand by itself, if any.
+    /// See [`CritHandler`].
+    fn crit_handler(&self) -> Option<&dyn CritHandler> {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn serialisation_order() {
+        let json = br#"{"zip":"DEF","b64":false,"crit":["b64"],"typ":"JWT","kid":"1","alg":"none","aaa":1}"#;
+        let header: Header = serde_json::from_slice(json).unwrap();
+        let expected = r#"{"alg":"none","kid":"1","typ":"JWT","crit":["b64"],"b64":false,"aaa":1,"zip":"DEF"}"#;
+        assert_eq!(serde_json::to_string(&header).unwrap(), expected);
+
+        // Decoding what was encoded is stable.
+        let again: Header = serde_json::from_str(expected).unwrap();
+        assert_eq!(again, header);
+        assert_eq!(serde_json::to_string(&again).unwrap(), expected);
+    }
+
+    #[test]
+    fn lenient_kid() {
+        let json = br#"{"alg":"none","kid":42}"#;
+        assert!(serde_json::from_slice::<Header>(json).is_err());
+        let header = Header::from_json_lenient(json).unwrap();
+        assert_eq!(header.key_id.as_deref(), Some("42"));
+
+        let header = Header::from_json_lenient(br#"{"alg":"none","kid":true}"#).unwrap();
+        assert_eq!(header.key_id.as_deref(), Some("true"));
+        let header = Header::from_json_lenient(br#"{"alg":"none","kid":"1"}"#).unwrap();
+        assert_eq!(header.key_id.as_deref(), Some("1"));
+        assert!(Header::from_json_lenient(br#"{"alg":"none","kid":[1]}"#).is_err());
+    }
+
     #[test]
     fn alg_value() {
+        #[allow(unused_imports)]
         use sign::SigningAlgorithm;
         macro_rules! test {
             ($value:expr => $expected:expr) => {
@@ -157,6 +435,12 @@ mod tests {
             test!(Algorithm::Signing(SigningAlgorithm::RS384) => "\"RS384\"");
             test!(Algorithm::Signing(SigningAlgorithm::RS512) => "\"RS512\"");
         }
+        #[cfg(feature = "rsa-pss")]
+        {
+            test!(Algorithm::Signing(SigningAlgorithm::PS256) => "\"PS256\"");
+            test!(Algorithm::Signing(SigningAlgorithm::PS384) => "\"PS384\"");
+            test!(Algorithm::Signing(SigningAlgorithm::PS512) => "\"PS512\"");
+        }
         #[cfg(feature = "ecdsa")]
         {
             test!(Algorithm::Signing(SigningAlgorithm::ES256) => "\"ES256\"");
@@ -164,4 +448,111 @@ mod tests {
             // test!(Algorithm::Signing(SigningAlgorithm::ES512) => "\"ES512\"");
         }
     }
+
+    #[test]
+    fn b64_crit() {
+        let mut header = Header::new(Algorithm::None);
+        header.b64 = Some(false);
+        assert!(!header.supports_required_extensions());
+
+        header.required_extensions = Some(vec!["b64".to_string()]);
+        assert!(header.supports_required_extensions());
+        assert!(!header.is_payload_encoded());
+
+        // b64 is listed, but the parameter itself is missing.
+        header.b64 = None;
+        assert!(!header.supports_required_extensions());
+
+        header.b64 = Some(true);
+        assert!(header.supports_required_extensions());
+        assert!(header.is_payload_encoded());
+
+        header.required_extensions = Some(vec!["b64".to_string(), "unknown".to_string()]);
+        assert!(!header.supports_required_extensions());
+        header.required_extensions = Some(vec![]);
+        assert!(!header.supports_required_extensions());
+    }
+
+    #[test]
+    fn crit_handler() {
+        struct TxnHandler;
+        impl CritHandler for TxnHandler {
+            fn understands(&self, name: &str) -> bool {
+                name == "txn"
+            }
+            fn validate(&self, _name: &str, header: &Header) -> bool {
+                header.extra["txn"] == "approved"
+            }
+        }
+
+        let mut header: Header =
+            serde_json::from_str(r#"{"alg":"none","crit":["txn"],"txn":"approved"}"#).unwrap();
+        assert_eq!(header.extra["txn"], "approved");
+        assert!(!header.supports_required_extensions());
+        assert!(header.supports_required_extensions_with(Some(&TxnHandler)));
+
+        header.extra.insert("txn".to_string(), "denied".into());
+        assert!(!header.supports_required_extensions_with(Some(&TxnHandler)));
+
+        // Listed, but not present.
+        header.extra.clear();
+        assert!(!header.supports_required_extensions_with(Some(&TxnHandler)));
+
+        // The handler doesn't get to override the built-in rules.
+        header.required_extensions = Some(vec!["txn".to_string(), "unknown".to_string()]);
+        header.extra.insert("txn".to_string(), "approved".into());
+        header.extra.insert("unknown".to_string(), true.into());
+        assert!(!header.supports_required_extensions_with(Some(&TxnHandler)));
+    }
+
+    #[test]
+    fn with_algorithm_and_key_id() {
+        let mut header = Header::new(Algorithm::None);
+        header.key_id = Some("old".to_string());
+        header.obj_type = Some("JWT".to_string());
+        let original = serde_json::to_value(&header).unwrap();
+
+        let changed = serde_json::to_value(header.with_key_id("new")).unwrap();
+        let mut expected = original.clone();
+        expected["kid"] = "new".into();
+        assert_eq!(changed, expected);
+
+        #[cfg(feature = "hmac-sha2")]
+        {
+            let changed = header.with_algorithm(Algorithm::Signing(sign::SigningAlgorithm::HS256));
+            let changed = serde_json::to_value(changed).unwrap();
+            let mut expected = original.clone();
+            expected["alg"] = "HS256".into();
+            assert_eq!(changed, expected);
+        }
+
+        // The original is left untouched.
+        assert_eq!(serde_json::to_value(&header).unwrap(), original);
+    }
+
+    #[test]
+    fn has_type() {
+        let mut header = Header::new(Algorithm::None);
+        assert!(!header.has_type("JWT"));
+        header.obj_type = Some("at+jwt".to_string());
+        assert!(header.has_type("at+jwt"));
+        assert!(header.has_type("application/AT+JWT"));
+        assert!(!header.has_type("JWT"));
+        header.obj_type = Some("Application/at+JWT".to_string());
+        assert!(header.has_type("at+jwt"));
+    }
+
+    #[test]
+    fn display_is_json() {
+        let mut header = Header::new(Algorithm::None);
+        header.obj_type = Some("JWT".to_string());
+
+        let displayed = format!("{header}");
+        let value: serde_json::Value =
+            serde_json::from_str(&displayed).expect("Display did not produce valid JSON");
+        assert_eq!(value["alg"], "none");
+        assert_eq!(value["typ"], "JWT");
+        assert!(!displayed.contains(char::is_whitespace));
+        assert!(!displayed.contains("null"));
+    }
 }
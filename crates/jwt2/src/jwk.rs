@@ -0,0 +1,313 @@
+//! JSON Web Keys (JWKs), as specified by [RFC 7517](https://www.rfc-editor.org/rfc/rfc7517.html).
+//!
+//! At the moment, only public keys are supported, since that's what gets published.
+//! Conversions to and from the algorithm types live with the algorithms themselves,
+//! e.g. [`ES256Public::from_jwk`](crate::sign::ecdsa::ES256Public::from_jwk).
+
+use crate::repr;
+use sha2::{Digest, Sha256};
+
+/// A JSON Web Key.
+///
+/// See [section 4 of RFC 7517](https://www.rfc-editor.org/rfc/rfc7517.html#section-4).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Jwk {
+    /// The key material itself, along with the `kty` parameter.
+    #[serde(flatten)]
+    pub key: JwkKey,
+
+    /// The ID of this key. Corresponds to the `kid` parameter.
+    #[serde(rename = "kid", skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+
+    /// What this key is intended to be used for (e.g. `sig`). Corresponds to the `use` parameter.
+    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
+    pub key_use: Option<String>,
+
+    /// The algorithm this key is intended to be used with. Corresponds to the `alg` parameter.
+    ///
+    /// This is kept as a string since JWKs may be meant for algorithms `jwt2` doesn't support.
+    #[serde(rename = "alg", skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+}
+
+/// The type-specific parameters of a [`Jwk`], tagged by the `kty` parameter.
+///
+/// All values are base64url-encoded, exactly as they appear in the JWK.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kty")]
+pub enum JwkKey {
+    /// An elliptic curve public key.
+    ///
+    /// See [section 6.2.1 of RFC 7518](https://www.rfc-editor.org/rfc/rfc7518.html#section-6.2.1).
+    ///
+    /// `y` may be empty if `x` is a compressed point, which is non-standard; see
+    /// [`ES256Public::from_jwk`](crate::sign::ecdsa::ES256Public::from_jwk).
+    #[serde(rename = "EC")]
+    Ec {
+        crv: String,
+        x: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        y: String,
+    },
+    /// An RSA public key.
+    ///
+    /// See [section 6.3.1 of RFC 7518](https://www.rfc-editor.org/rfc/rfc7518.html#section-6.3.1).
+    #[serde(rename = "RSA")]
+    Rsa { n: String, e: String },
+    /// An octet key pair, i.e. an EdDSA (or ECDH) public key.
+    ///
+    /// See [section 2 of RFC 8037](https://www.rfc-editor.org/rfc/rfc8037.html#section-2).
+    #[serde(rename = "OKP")]
+    Okp { crv: String, x: String },
+}
+
+impl Jwk {
+    pub fn new(key: JwkKey) -> Self {
+        Self {
+            key,
+            key_id: None,
+            key_use: None,
+            algorithm: None,
+        }
+    }
+
+    /// Computes the SHA-256 thumbprint of this key, base64url-encoded.
+    ///
+    /// Only the required parameters of the key are included, so the thumbprint doesn't depend
+    /// on `kid`, `use` or `alg`.
+    ///
+    /// EC keys given as a compressed point (see [`JwkKey::Ec`]) are decompressed first, so
+    /// they have the same thumbprint as the uncompressed key. That needs the `ecdsa` feature;
+    /// without it, or if the point isn't valid, the key is hashed as it is.
+    ///
+    /// See [RFC 7638](https://www.rfc-editor.org/rfc/rfc7638.html).
+    pub fn thumbprint(&self) -> String {
+        if let Some(decompressed) = self.decompressed() {
+            return decompressed.thumbprint();
+        }
+        // RFC 7638 requires the members to be in lexicographic order without any whitespace,
+        // which is easier to guarantee by hand than through serde.
+        let json = |s: &str| serde_json::Value::from(s).to_string();
+        let canonical = match &self.key {
+            JwkKey::Ec { crv, x, y } => format!(
+                r#"{{"crv":{},"kty":"EC","x":{},"y":{}}}"#,
+                json(crv),
+                json(x),
+                json(y)
+            ),
+            JwkKey::Rsa { n, e } => {
+                format!(r#"{{"e":{},"kty":"RSA","n":{}}}"#, json(e), json(n))
+            }
+            JwkKey::Okp { crv, x } => {
+                format!(r#"{{"crv":{},"kty":"OKP","x":{}}}"#, json(crv), json(x))
+            }
+        };
+        repr::encode_bytes_as_base64url(&Sha256::digest(canonical.as_bytes()))
+    }
+
+    /// If this is an EC key with a compressed point, gets the same key with both coordinates.
+    fn decompressed(&self) -> Option<Jwk> {
+        #[cfg(feature = "ecdsa")]
+        if let JwkKey::Ec { crv, y, .. } = &self.key {
+            if y.is_empty() {
+                return match crv.as_str() {
+                    "P-256" => self.to_es256_public().ok().map(|key| key.to_jwk()),
+                    "P-384" => self.to_es384_public().ok().map(|key| key.to_jwk()),
+                    _ => None,
+                };
+            }
+        }
+        None
+    }
+
+    /// Sets [`Jwk::key_id`] to the [thumbprint](Jwk::thumbprint) of this key.
+    pub fn with_thumbprint_key_id(mut self) -> Self {
+        self.key_id = Some(self.thumbprint());
+        self
+    }
+
+    /// Imports this key as an `ES256` verifier. See [`ES256Public::from_jwk`].
+    ///
+    /// [`ES256Public::from_jwk`]: crate::sign::ecdsa::ES256Public::from_jwk
+    #[cfg(feature = "ecdsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ecdsa")))]
+    pub fn to_es256_public(&self) -> Result<crate::sign::ecdsa::ES256Public, JwkError> {
+        crate::sign::ecdsa::ES256Public::from_jwk(self)
+    }
+
+    /// Imports this key as an `ES384` verifier. See [`ES384Public::from_jwk`].
+    ///
+    /// [`ES384Public::from_jwk`]: crate::sign::ecdsa::ES384Public::from_jwk
+    #[cfg(feature = "ecdsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ecdsa")))]
+    pub fn to_es384_public(&self) -> Result<crate::sign::ecdsa::ES384Public, JwkError> {
+        crate::sign::ecdsa::ES384Public::from_jwk(self)
+    }
+
+    /// Imports this key as an `EdDSA` verifier. See [`EdDSAPublic::from_jwk`].
+    ///
+    /// [`EdDSAPublic::from_jwk`]: crate::sign::eddsa::EdDSAPublic::from_jwk
+    #[cfg(feature = "eddsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "eddsa")))]
+    pub fn to_eddsa_public(&self) -> Result<crate::sign::eddsa::EdDSAPublic, JwkError> {
+        crate::sign::eddsa::EdDSAPublic::from_jwk(self)
+    }
+}
+
+/// A JWK Set, e.g. the contents of a `/.well-known/jwks.json`.
+///
+/// Keys are identified by their [`Jwk::key_id`]; keys without one can be added, but not found
+/// or removed by ID.
+///
+/// See [section 5 of RFC 7517](https://www.rfc-editor.org/rfc/rfc7517.html#section-5).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `jwk` to this set, replacing (and returning) the key with the same ID if there is
+    /// one.
+    pub fn add(&mut self, jwk: Jwk) -> Option<Jwk> {
+        let existing = jwk
+            .key_id
+            .as_deref()
+            .and_then(|kid| self.position(kid));
+        match existing {
+            Some(i) => Some(std::mem::replace(&mut self.keys[i], jwk)),
+            None => {
+                self.keys.push(jwk);
+                None
+            }
+        }
+    }
+
+    /// Like [`JwkSet::add`], but for chaining.
+    pub fn with(mut self, jwk: Jwk) -> Self {
+        self.add(jwk);
+        self
+    }
+
+    /// Removes the key with the ID `kid`, returning it if it was present.
+    pub fn remove_by_kid(&mut self, kid: &str) -> Option<Jwk> {
+        self.position(kid).map(|i| self.keys.remove(i))
+    }
+
+    /// Finds the key with the ID `kid`.
+    pub fn find(&self, kid: &str) -> Option<&Jwk> {
+        self.position(kid).map(|i| &self.keys[i])
+    }
+
+    fn position(&self, kid: &str) -> Option<usize> {
+        self.keys
+            .iter()
+            .position(|jwk| jwk.key_id.as_deref() == Some(kid))
+    }
+}
+
+impl FromIterator<Jwk> for JwkSet {
+    fn from_iter<I: IntoIterator<Item = Jwk>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JwkError {
+    #[error("the key is not of the expected type")]
+    WrongKeyType,
+    #[error("the curve {0} is not the expected curve")]
+    WrongCurve(String),
+    #[error("invalid base64: {0}")]
+    Base64(base64ct::Error),
+    #[error("the key parameters are invalid")]
+    InvalidKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example from [section 3.1 of RFC 7638](https://www.rfc-editor.org/rfc/rfc7638.html#section-3.1).
+    #[test]
+    fn rfc7638_thumbprint() {
+        let jwk: Jwk = serde_json::from_str(
+            r#"{
+                "kty": "RSA",
+                "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+                "e": "AQAB",
+                "alg": "RS256",
+                "kid": "2011-04-29"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(jwk.key_id.as_deref(), Some("2011-04-29"));
+        assert_eq!(
+            jwk.thumbprint(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+
+    /// The example from [appendix A.3 of RFC 8037](https://www.rfc-editor.org/rfc/rfc8037.html#appendix-A.3).
+    #[test]
+    fn rfc8037_thumbprint() {
+        let jwk: Jwk = serde_json::from_str(
+            r#"{"kty":"OKP","crv":"Ed25519","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            jwk.thumbprint(),
+            "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let jwk = Jwk::new(JwkKey::Ec {
+            crv: "P-256".to_string(),
+            x: "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU".to_string(),
+            y: "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0".to_string(),
+        })
+        .with_thumbprint_key_id();
+
+        let json = serde_json::to_value(&jwk).unwrap();
+        assert_eq!(json["kty"], "EC");
+        assert!(json.get("use").is_none());
+        assert_eq!(serde_json::from_value::<Jwk>(json).unwrap(), jwk);
+    }
+
+    #[test]
+    fn jwk_set() {
+        let key = |kid: &str, n: &str| {
+            let mut jwk = Jwk::new(JwkKey::Rsa {
+                n: n.to_string(),
+                e: "AQAB".to_string(),
+            });
+            jwk.key_id = Some(kid.to_string());
+            jwk
+        };
+
+        let mut set = JwkSet::new().with(key("a", "AA")).with(key("b", "Ag"));
+        assert_eq!(set.find("b"), Some(&key("b", "Ag")));
+        assert_eq!(set.find("c"), None);
+
+        // Adding a key with an existing ID replaces it.
+        assert_eq!(set.add(key("a", "AQ")), Some(key("a", "AA")));
+        assert_eq!(set.keys.len(), 2);
+        assert_eq!(set.find("a"), Some(&key("a", "AQ")));
+
+        assert_eq!(set.remove_by_kid("a"), Some(key("a", "AQ")));
+        assert_eq!(set.remove_by_kid("a"), None);
+
+        let json = serde_json::to_value(&set).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "keys": [{ "kty": "RSA", "n": "Ag", "e": "AQAB", "kid": "b" }] })
+        );
+        assert_eq!(serde_json::from_value::<JwkSet>(json).unwrap(), set);
+    }
+}
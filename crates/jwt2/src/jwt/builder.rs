@@ -0,0 +1,169 @@
+//! A high-level facade over [`JwtData`] and [`RawJwt`].
+
+use crate::jwt::{JwtCreateError, JwtData, RawJwt, ValidationOptions, VerifyError};
+use crate::{Header, JwsSigner, JwsVerifier};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// A JWT with typed claims, covering the whole lifecycle of a token in a couple of calls.
+///
+/// Minting a token:
+/// ```
+/// # #[cfg(feature = "hmac-sha2")] {
+/// use jwt2::jwt::Jwt;
+/// use jwt2::sign::HS256;
+///
+/// let hs256 = HS256::new(b"your-256-bit-secret").expect("Could not construct HS256");
+/// let token = Jwt::builder()
+///     .claim("sub", "1234567890")
+///     .claim("exp", u64::MAX)
+///     .sign(&hs256)
+///     .expect("Could not sign");
+/// # }
+/// ```
+///
+/// Consuming one:
+/// ```
+/// # #[cfg(feature = "hmac-sha2")] {
+/// use jwt2::jwt::{Jwt, ValidationOptions};
+/// use jwt2::sign::HS256;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Claims {
+///     sub: String,
+/// }
+///
+/// let hs256 = HS256::new(b"your-256-bit-secret").expect("Could not construct HS256");
+/// let token = Jwt::builder()
+///     .claim("sub", "1234567890")
+///     .claim("exp", u64::MAX)
+///     .sign(&hs256)
+///     .unwrap();
+///
+/// let jwt = Jwt::<Claims>::verify(&token, &hs256, &ValidationOptions::default())
+///     .expect("Could not verify");
+/// assert_eq!(jwt.claims.sub, "1234567890");
+/// # }
+/// ```
+///
+/// This is only a convenience; [`JwtData`] and [`RawJwt`] remain available for anything it
+/// doesn't cover.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jwt<Claims = Value> {
+    pub header: Header,
+    pub claims: Claims,
+}
+
+impl Jwt {
+    /// Starts building a token out of individual claims.
+    pub fn builder() -> JwtBuilder {
+        JwtBuilder::default()
+    }
+}
+
+impl<Claims> Jwt<Claims>
+where
+    Claims: DeserializeOwned,
+{
+    /// Decodes and fully verifies `token` (see [`RawJwt::verify`]), then deserialises its claims.
+    pub fn verify<Verifier>(
+        token: &str,
+        verifier: &Verifier,
+        options: &ValidationOptions,
+    ) -> Result<Self, VerifyError>
+    where
+        Verifier: ?Sized + JwsVerifier,
+    {
+        let jwt = RawJwt::decode(token)?;
+        jwt.verify(verifier, options)?;
+        Ok(jwt.parse_owned::<Claims>()?.into())
+    }
+}
+
+impl<Claims> From<JwtData<Claims>> for Jwt<Claims> {
+    fn from(data: JwtData<Claims>) -> Self {
+        Self {
+            header: data.header,
+            claims: data.claims,
+        }
+    }
+}
+impl<Claims> From<Jwt<Claims>> for JwtData<Claims> {
+    fn from(jwt: Jwt<Claims>) -> Self {
+        Self {
+            header: jwt.header,
+            claims: jwt.claims,
+        }
+    }
+}
+
+/// Builds a token claim by claim; see [`Jwt::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct JwtBuilder {
+    header: Option<Header>,
+    claims: Map<String, Value>,
+    #[cfg(feature = "rand")]
+    random_jwt_id: bool,
+}
+
+impl JwtBuilder {
+    /// Uses `header` instead of the one [recommended](Header::recommended) by the signer.
+    pub fn header(mut self, header: Header) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Sets the claim `name` to `value`, replacing any previous value.
+    pub fn claim(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.claims.insert(name.into(), value.into());
+        self
+    }
+
+    /// Generates a random `jti` claim when signing, unless one has been set.
+    ///
+    /// This is off by default so that signing the same claims gives the same token.
+    /// See [`claims::random_jwt_id`](super::claims::random_jwt_id).
+    #[cfg(feature = "rand")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    pub fn random_jwt_id(mut self) -> Self {
+        self.random_jwt_id = true;
+        self
+    }
+
+    /// Signs the token using `signer`.
+    pub fn sign<Signer>(self, signer: &Signer) -> Result<String, JwtCreateError>
+    where
+        Signer: ?Sized + JwsSigner,
+    {
+        #[cfg(feature = "rand")]
+        let claims = {
+            let mut claims = self.claims;
+            if self.random_jwt_id {
+                claims
+                    .entry("jti")
+                    .or_insert_with(|| super::claims::random_jwt_id().into());
+            }
+            claims
+        };
+        #[cfg(not(feature = "rand"))]
+        let claims = self.claims;
+        JwtData {
+            header: self.header.unwrap_or_else(|| Header::recommended(signer)),
+            claims: Value::Object(claims),
+        }
+        .sign_with(signer)
+    }
+}
+
+/// Signs a token whose only claim is `"sub": "1234567890"`, for tests that just need some token
+/// from `signer`.
+#[cfg(all(test, any(feature = "hmac-sha2", feature = "ecdsa", feature = "rsa-pkcs1")))]
+pub(crate) fn example_token<Signer>(signer: &Signer) -> String
+where
+    Signer: ?Sized + JwsSigner,
+{
+    Jwt::builder()
+        .claim("sub", "1234567890")
+        .sign(signer)
+        .expect("Could not sign")
+}
@@ -0,0 +1,351 @@
+//! Helpers for dealing with the claims of a JWT.
+//!
+//! `jwt2` doesn't force any particular claims structure upon you, so the functions in here work
+//! on plain [`serde_json::Value`]s. [`RegisteredClaims`] is provided for convenience.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+/// The registered claims of a JWT that `jwt2` knows about.
+///
+/// See [section 4.1 of RFC 7519](https://www.rfc-editor.org/rfc/rfc7519.html#section-4.1).
+/// The temporal claims accept any NumericDate, including non-integer values, which are
+/// truncated to whole seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RegisteredClaims {
+    #[serde(rename = "iss", default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(
+        rename = "exp",
+        default,
+        deserialize_with = "deserialize_numeric_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expires_at: Option<u64>,
+    #[serde(
+        rename = "nbf",
+        default,
+        deserialize_with = "deserialize_numeric_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub not_before: Option<u64>,
+    #[serde(
+        rename = "iat",
+        default,
+        deserialize_with = "deserialize_numeric_date",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub issued_at: Option<u64>,
+    #[serde(rename = "jti", default, skip_serializing_if = "Option::is_none")]
+    pub jwt_id: Option<String>,
+}
+
+impl RegisteredClaims {
+    /// Sets [`jwt_id`](RegisteredClaims::jwt_id) to a random ID, unless it is already set.
+    ///
+    /// See [`random_jwt_id`].
+    #[cfg(feature = "rand")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    pub fn with_random_jwt_id(mut self) -> Self {
+        self.jwt_id.get_or_insert_with(random_jwt_id);
+        self
+    }
+}
+
+/// Generates a random `jti` (JWT ID): 128 bits from the OS RNG, base64url-encoded.
+///
+/// That is enough that IDs won't collide, which is what
+/// [section 4.1.7 of RFC 7519](https://www.rfc-editor.org/rfc/rfc7519.html#section-4.1.7) asks
+/// for.
+#[cfg(feature = "rand")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+pub fn random_jwt_id() -> String {
+    random_jwt_id_with(&mut rand_core::OsRng)
+}
+
+/// Like [`random_jwt_id`], but uses `rng` instead of the OS RNG.
+#[cfg(feature = "rand")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+pub fn random_jwt_id_with<R>(rng: &mut R) -> String
+where
+    R: rand_core::CryptoRngCore,
+{
+    let mut bytes = [0u8; 16];
+    rng.fill_bytes(&mut bytes);
+    crate::repr::encode_bytes_as_base64url(&bytes)
+}
+
+/// Typed claims that keep any other claims around, so they survive being decoded and signed
+/// again.
+///
+/// The claims that `Known` deserialises are taken out first, and whatever is left ends up in
+/// [`extra`](ClaimsWithExtra::extra). Make sure `Known` doesn't use `#[serde(flatten)]` or
+/// `#[serde(deny_unknown_fields)]` itself, since either would leave nothing for `extra`.
+///
+/// ```
+/// use jwt2::jwt::claims::{ClaimsWithExtra, RegisteredClaims};
+/// use serde_json::json;
+///
+/// let claims: ClaimsWithExtra<RegisteredClaims> =
+///     serde_json::from_value(json!({ "sub": "1234567890", "name": "John Doe" })).unwrap();
+/// assert_eq!(claims.known.subject.as_deref(), Some("1234567890"));
+/// assert_eq!(claims.extra["name"], "John Doe");
+/// assert!(!claims.extra.contains_key("sub"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ClaimsWithExtra<Known> {
+    /// The claims you know about.
+    #[serde(flatten)]
+    pub known: Known,
+    /// All other claims.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl<Known> ClaimsWithExtra<Known> {
+    /// Creates claims without any extra claims.
+    pub fn new(known: Known) -> Self {
+        Self {
+            known,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Deserialises an optional NumericDate, accepting both integers and floating-point numbers.
+///
+/// For use with `#[serde(deserialize_with = "...")]`; see [`numeric_date`] for the semantics.
+pub fn deserialize_numeric_date<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => numeric_date(&value)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom("invalid NumericDate")),
+    }
+}
+
+/// Parses a list of scopes from a claim value.
+///
+/// OAuth-style tokens don't agree on how scopes are represented, so this accepts both forms
+/// that are seen in the wild:
+/// - a space-delimited string (e.g. the `scope` claim, see
+///   [section 3.3 of RFC 6749](https://www.rfc-editor.org/rfc/rfc6749.html#section-3.3)),
+/// - an array of strings (e.g. the `scp` claim some providers use).
+///
+/// Anything else (including non-string array elements) is ignored.
+///
+/// ```
+/// use jwt2::jwt::claims::parse_scopes;
+/// use serde_json::json;
+///
+/// assert_eq!(parse_scopes(&json!("read write")), ["read", "write"]);
+/// assert_eq!(parse_scopes(&json!(["read", "write"])), ["read", "write"]);
+/// assert!(parse_scopes(&json!(42)).is_empty());
+/// ```
+pub fn parse_scopes(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(scopes) => scopes
+            .split(' ')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Value::Array(scopes) => scopes
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Gets all scopes granted by a set of claims, i.e. the scopes in the `scope` and `scp` claims.
+///
+/// See [`parse_scopes`] for the accepted representations.
+pub fn granted_scopes(claims: &Value) -> Vec<String> {
+    let mut scopes = Vec::new();
+    for claim in ["scope", "scp"] {
+        if let Some(value) = claims.get(claim) {
+            scopes.extend(parse_scopes(value));
+        }
+    }
+    scopes
+}
+
+/// Reads a NumericDate (seconds since the UNIX epoch) from a claim value.
+///
+/// NumericDates aren't required to be integers, so floating-point values are accepted too and
+/// truncated towards the floor. Negative and non-finite values are rejected.
+///
+/// See [section 2 of RFC 7519](https://www.rfc-editor.org/rfc/rfc7519.html#section-2).
+///
+/// ```
+/// use jwt2::jwt::claims::numeric_date;
+/// use serde_json::json;
+///
+/// assert_eq!(numeric_date(&json!(1516239022)), Some(1516239022));
+/// assert_eq!(numeric_date(&json!(1516239022.7)), Some(1516239022));
+/// assert_eq!(numeric_date(&json!("1516239022")), None);
+/// ```
+pub fn numeric_date(value: &Value) -> Option<u64> {
+    if let Some(date) = value.as_u64() {
+        return Some(date);
+    }
+    match value.as_f64()? {
+        date if date.is_finite() && date >= 0.0 => Some(date.floor() as u64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn scopes_string() {
+        assert_eq!(
+            parse_scopes(&json!("read write  admin")),
+            ["read", "write", "admin"]
+        );
+        assert!(parse_scopes(&json!("")).is_empty());
+    }
+
+    #[test]
+    fn scopes_array() {
+        assert_eq!(parse_scopes(&json!(["read", "write"])), ["read", "write"]);
+        assert_eq!(parse_scopes(&json!(["read", 1, null])), ["read"]);
+    }
+
+    #[test]
+    fn granted_scopes_from_both_claims() {
+        let claims = json!({ "scope": "read", "scp": ["write"] });
+        assert_eq!(granted_scopes(&claims), ["read", "write"]);
+    }
+
+    #[test]
+    fn numeric_date_float() {
+        assert_eq!(numeric_date(&json!(1516239022.7)), Some(1516239022));
+        assert_eq!(numeric_date(&json!(1516239022.0)), Some(1516239022));
+        assert_eq!(numeric_date(&json!(-1.5)), None);
+        assert_eq!(numeric_date(&json!(null)), None);
+    }
+
+    #[test]
+    fn registered_claims_float_dates() {
+        let claims: RegisteredClaims = serde_json::from_value(json!({
+            "sub": "1234567890",
+            "iat": 1516239022,
+            "nbf": 1516239022.2,
+            "exp": 1516239022.7,
+        }))
+        .unwrap();
+        assert_eq!(claims.subject.as_deref(), Some("1234567890"));
+        assert_eq!(claims.issued_at, Some(1516239022));
+        assert_eq!(claims.not_before, Some(1516239022));
+        assert_eq!(claims.expires_at, Some(1516239022));
+
+        let claims: RegisteredClaims = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(claims, RegisteredClaims::default());
+        assert!(serde_json::from_value::<RegisteredClaims>(json!({ "exp": "soon" })).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "rand", feature = "hmac-sha2"))]
+    fn random_jwt_id() {
+        use crate::jwt::{Jwt, RawJwt};
+        use crate::sign::HS256;
+
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let mint = || {
+            let token = Jwt::builder()
+                .claim("sub", "1234567890")
+                .random_jwt_id()
+                .sign(&hs256)
+                .unwrap();
+            let claims: RegisteredClaims = RawJwt::decode(&token)
+                .unwrap()
+                .parse_without_verification()
+                .unwrap();
+            claims.jwt_id.unwrap()
+        };
+        let (first, second) = (mint(), mint());
+        assert_eq!(first.len(), 22);
+        assert_ne!(first, second);
+
+        // An explicit `jti` is left alone.
+        let token = Jwt::builder()
+            .claim("jti", "fixed")
+            .random_jwt_id()
+            .sign(&hs256)
+            .unwrap();
+        let claims: RegisteredClaims = RawJwt::decode(&token)
+            .unwrap()
+            .parse_without_verification()
+            .unwrap();
+        assert_eq!(claims.jwt_id.as_deref(), Some("fixed"));
+
+        let claims = RegisteredClaims::default().with_random_jwt_id();
+        assert!(!claims.jwt_id.unwrap().is_empty());
+        let claims = RegisteredClaims {
+            jwt_id: Some("fixed".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(claims.with_random_jwt_id().jwt_id.as_deref(), Some("fixed"));
+    }
+
+    #[test]
+    #[cfg(feature = "hmac-sha2")]
+    fn claims_with_extra_round_trip() {
+        use crate::jwt::{JwtData, RawJwt};
+        use crate::sign::HS256;
+        use crate::Header;
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Known {
+            sub: String,
+            admin: bool,
+        }
+
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let original = json!({
+            "sub": "1234567890",
+            "admin": true,
+            "name": "John Doe",
+            "groups": ["a", "b"],
+        });
+        let token = crate::jwt::sign_value(&Header::recommended(&hs256), &original, &hs256)
+            .expect("Could not sign");
+
+        let data = RawJwt::decode(&token)
+            .unwrap()
+            .parse::<ClaimsWithExtra<Known>>()
+            .expect("Could not parse");
+        assert_eq!(
+            data.claims.known,
+            Known {
+                sub: "1234567890".to_string(),
+                admin: true
+            }
+        );
+        assert_eq!(data.claims.extra.len(), 2);
+        assert_eq!(data.claims.extra["groups"], json!(["a", "b"]));
+
+        // Re-signing reproduces every claim, known or not.
+        let token = JwtData {
+            header: data.header,
+            claims: data.claims,
+        }
+        .sign_with(&hs256)
+        .expect("Could not sign");
+        let resigned = RawJwt::decode(&token)
+            .unwrap()
+            .parse::<Value>()
+            .expect("Could not parse");
+        assert_eq!(resigned.claims, original);
+    }
+}
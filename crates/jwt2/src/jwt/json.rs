@@ -0,0 +1,109 @@
+//! The flattened JWS JSON serialisation.
+//!
+//! See [section 7.2.2 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-7.2.2).
+//! The general serialisation (with multiple signatures) is not supported.
+
+use crate::jwt::{JwtDecodeError, RawJwt};
+use crate::{repr, sign, Header, JwsVerifier};
+use serde::de::DeserializeOwned;
+
+/// A JWS in the flattened JSON serialisation.
+///
+/// ```json
+/// {
+///   "protected": "<base64url(header)>",
+///   "header": { "kid": "unprotected header parameters" },
+///   "payload": "<base64url(payload)>",
+///   "signature": "<base64url(signature)>"
+/// }
+/// ```
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FlattenedJws {
+    /// The protected header, still base64url-encoded.
+    pub protected: String,
+    /// The unprotected header parameters, if any.
+    ///
+    /// These aren't covered by the signature, so they're only exposed as-is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<serde_json::Map<String, serde_json::Value>>,
+    /// The payload, still base64url-encoded (unless the `b64` header parameter is `false`).
+    pub payload: String,
+    /// The signature, still base64url-encoded.
+    pub signature: String,
+}
+
+impl FlattenedJws {
+    /// Decodes a JWS from its flattened JSON serialisation.
+    pub fn decode(input: &str) -> Result<Self, JwtDecodeError> {
+        Ok(repr::json::from_vec(input.as_bytes().to_vec())?)
+    }
+
+    /// Converts a compact JWS into the flattened JSON serialisation.
+    pub fn from_compact(jwt: &RawJwt) -> Self {
+        let [protected, payload, signature] = jwt.segments();
+        Self {
+            protected: protected.to_string(),
+            header: None,
+            payload: payload.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    /// Decodes the protected header.
+    pub fn protected_header(&self) -> Result<Header, JwtDecodeError> {
+        super::decode_header(&self.protected)
+    }
+
+    /// Gets the JWS signing input, i.e. `<protected>.<payload>`.
+    pub fn signing_input(&self) -> String {
+        format!("{}.{}", self.protected, self.payload)
+    }
+
+    /// Checks the signature using `verifier`, like [`RawJwt::verify_signature`].
+    pub fn verify_signature<Verifier>(&self, verifier: &Verifier) -> Result<bool, JwtDecodeError>
+    where
+        Verifier: ?Sized + JwsVerifier,
+    {
+        let header = self.protected_header()?;
+        let signature = repr::decode_bytes_from_base64url(&self.signature)
+            .map_err(|e| JwtDecodeError::Decode(repr::DecodeError::Base64(e)))?;
+        Ok(sign::verify_with_header(
+            verifier,
+            &header,
+            self.signing_input(),
+            &signature,
+        ))
+    }
+
+    /// Deserialises the payload. This does not verify anything.
+    pub fn parse<Claims>(&self) -> Result<Claims, JwtDecodeError>
+    where
+        Claims: DeserializeOwned,
+    {
+        let payload = if self.protected_header()?.is_payload_encoded() {
+            repr::decode_bytes_from_base64url(&self.payload).map_err(repr::DecodeError::Base64)?
+        } else {
+            self.payload.as_bytes().to_vec()
+        };
+        Ok(repr::json::from_vec(payload)?)
+    }
+}
+
+/// A JWS in either serialisation; see [`decode_any`](crate::jwt::decode_any).
+pub enum DecodedJws<'a> {
+    Compact(RawJwt<'a>),
+    Json(FlattenedJws),
+}
+
+impl DecodedJws<'_> {
+    /// Checks the signature using `verifier`, regardless of the serialisation.
+    pub fn verify_signature<Verifier>(&self, verifier: &Verifier) -> Result<bool, JwtDecodeError>
+    where
+        Verifier: ?Sized + JwsVerifier,
+    {
+        match self {
+            Self::Compact(jwt) => Ok(jwt.verify_signature(verifier)),
+            Self::Json(jws) => jws.verify_signature(verifier),
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! Validation profiles for well-known kinds of JWTs.
+//!
+//! These check the rules a specification imposes on the claims, on top of the generic checks
+//! of [`ValidationOptions`]. Like those, they don't verify the signature; do that first.
+
+use crate::jwt::{ClaimsError, ValidationOptions};
+use crate::SigningAlgorithm;
+use serde_json::Value;
+use std::time::SystemTime;
+
+/// What an ID token is expected to look like; see [`validate_id_token`].
+#[derive(Debug, Clone)]
+pub struct IdTokenOpts {
+    /// The issuer identifier of the OpenID provider, which `iss` must match exactly.
+    pub issuer: String,
+    /// The client ID of the relying party (i.e. you), which `aud` must contain.
+    pub client_id: String,
+    /// The nonce sent in the authentication request, if any, which `nonce` must match.
+    pub nonce: Option<String>,
+    /// The generic checks, such as `exp` and leeways.
+    ///
+    /// `exp` is always required for ID tokens, regardless of
+    /// [`ValidationOptions::require_exp`].
+    pub validation: ValidationOptions,
+}
+
+impl IdTokenOpts {
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            nonce: None,
+            validation: ValidationOptions::default(),
+        }
+    }
+
+    /// Sets the nonce that the token must contain.
+    pub fn with_nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+}
+
+/// Validates the claims of an OpenID Connect ID token, using the system clock as the current
+/// time.
+///
+/// This follows [section 3.1.3.7 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#IDTokenValidation):
+/// - `iss`, `sub`, `aud`, `exp` and `iat` must be present;
+/// - `iss` must be [`IdTokenOpts::issuer`];
+/// - `aud` must contain [`IdTokenOpts::client_id`];
+/// - if there are multiple audiences, `azp` must be present, and if `azp` is present, it must be
+///   the client ID;
+/// - if [`IdTokenOpts::nonce`] is set, `nonce` must be equal to it;
+/// - and everything [`ValidationOptions::validate_claims`] checks.
+///
+/// ```
+/// use jwt2::jwt::profiles::{validate_id_token, IdTokenError, IdTokenOpts};
+/// use serde_json::json;
+///
+/// let opts = IdTokenOpts::new("https://server.example.com", "s6BhdRkqt3")
+///     .with_nonce("n-0S6_WzA2Mj");
+/// let claims = json!({
+///     "iss": "https://server.example.com",
+///     "sub": "24400320",
+///     "aud": "s6BhdRkqt3",
+///     "nonce": "n-0S6_WzA2Mj",
+///     "exp": u64::MAX,
+///     "iat": 1311280970,
+/// });
+/// assert!(validate_id_token(&claims, &opts).is_ok());
+///
+/// let opts = opts.with_nonce("something else");
+/// assert!(matches!(validate_id_token(&claims, &opts), Err(IdTokenError::NonceMismatch)));
+/// ```
+pub fn validate_id_token(claims: &Value, opts: &IdTokenOpts) -> Result<(), IdTokenError> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    validate_id_token_at(claims, opts, now)
+}
+
+/// Like [`validate_id_token`], with `now` (in seconds since the Unix epoch) as the current time.
+pub fn validate_id_token_at(
+    claims: &Value,
+    opts: &IdTokenOpts,
+    now: u64,
+) -> Result<(), IdTokenError> {
+    let claims_object = claims.as_object().ok_or(ClaimsError::NotAnObject)?;
+    for claim in ["iss", "sub", "aud", "exp", "iat"] {
+        if !claims_object.contains_key(claim) {
+            return Err(IdTokenError::MissingClaim(claim));
+        }
+    }
+
+    let string = |claim: &'static str| {
+        claims[claim]
+            .as_str()
+            .ok_or(ClaimsError::InvalidClaim(claim))
+    };
+    if string("iss")? != opts.issuer {
+        return Err(IdTokenError::IssuerMismatch);
+    }
+    string("sub")?;
+
+    let audiences: Vec<&str> = match &claims["aud"] {
+        Value::String(aud) => vec![aud.as_str()],
+        Value::Array(auds) => auds
+            .iter()
+            .map(Value::as_str)
+            .collect::<Option<_>>()
+            .ok_or(ClaimsError::InvalidClaim("aud"))?,
+        _ => return Err(ClaimsError::InvalidClaim("aud").into()),
+    };
+    if !audiences.contains(&opts.client_id.as_str()) {
+        return Err(IdTokenError::AudienceMismatch);
+    }
+    match claims.get("azp") {
+        Some(_) if string("azp")? != opts.client_id => {
+            return Err(IdTokenError::AuthorizedPartyMismatch)
+        }
+        Some(_) => {}
+        None if audiences.len() > 1 => return Err(IdTokenError::MissingClaim("azp")),
+        None => {}
+    }
+
+    if let Some(ref expected) = opts.nonce {
+        if !claims_object.contains_key("nonce") {
+            return Err(IdTokenError::MissingClaim("nonce"));
+        }
+        if string("nonce")? != expected {
+            return Err(IdTokenError::NonceMismatch);
+        }
+    }
+
+    crate::jwt::claims::numeric_date(&claims["iat"]).ok_or(ClaimsError::InvalidClaim("iat"))?;
+    opts.validation.validate_claims_at(claims, now)?;
+    Ok(())
+}
+
+/// Computes the `at_hash` claim for `access_token`, for an ID token signed with `alg`.
+///
+/// This is the left half of the hash of the access token, base64url-encoded, where the hash
+/// is the one used by `alg` (e.g. SHA-256 for `RS256`, `ES256` and `HS256`).
+/// Returns `None` if `alg` doesn't use a SHA-2 hash.
+///
+/// See [section 3.2.2.9 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#ImplicitTokenValidation).
+#[cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa")))
+)]
+pub fn compute_at_hash(access_token: &str, alg: SigningAlgorithm) -> Option<String> {
+    use sha2::{Digest, Sha256, Sha384, Sha512};
+
+    #[allow(unreachable_patterns)]
+    let bits = match alg {
+        #[cfg(feature = "hmac-sha2")]
+        SigningAlgorithm::HS256 => 256,
+        #[cfg(feature = "hmac-sha2")]
+        SigningAlgorithm::HS384 => 384,
+        #[cfg(feature = "hmac-sha2")]
+        SigningAlgorithm::HS512 => 512,
+        #[cfg(feature = "rsa-pkcs1")]
+        SigningAlgorithm::RS256 => 256,
+        #[cfg(feature = "rsa-pkcs1")]
+        SigningAlgorithm::RS384 => 384,
+        #[cfg(feature = "rsa-pkcs1")]
+        SigningAlgorithm::RS512 => 512,
+        #[cfg(feature = "rsa-pss")]
+        SigningAlgorithm::PS256 => 256,
+        #[cfg(feature = "rsa-pss")]
+        SigningAlgorithm::PS384 => 384,
+        #[cfg(feature = "rsa-pss")]
+        SigningAlgorithm::PS512 => 512,
+        #[cfg(feature = "ecdsa")]
+        SigningAlgorithm::ES256 => 256,
+        #[cfg(feature = "ecdsa")]
+        SigningAlgorithm::ES384 => 384,
+        _ => return None,
+    };
+    let digest = match bits {
+        256 => Sha256::digest(access_token).to_vec(),
+        384 => Sha384::digest(access_token).to_vec(),
+        _ => Sha512::digest(access_token).to_vec(),
+    };
+    Some(crate::repr::encode_bytes_as_base64url(
+        &digest[..digest.len() / 2],
+    ))
+}
+
+/// Computes the `c_hash` claim for an authorization `code`, for an ID token signed with `alg`.
+///
+/// This is computed the same way as [`compute_at_hash`].
+///
+/// See [section 3.3.2.11 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#HybridIDToken).
+#[cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa")))
+)]
+pub fn compute_c_hash(code: &str, alg: SigningAlgorithm) -> Option<String> {
+    compute_at_hash(code, alg)
+}
+
+/// Checks that the `at_hash` claim matches `access_token`; see [`compute_at_hash`].
+///
+/// `alg` must be the algorithm the ID token was signed with.
+#[cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa")))
+)]
+pub fn validate_at_hash(
+    claims: &Value,
+    access_token: &str,
+    alg: SigningAlgorithm,
+) -> Result<(), IdTokenError> {
+    validate_hash(claims, "at_hash", access_token, alg)
+}
+
+/// Checks that the `c_hash` claim matches `code`; see [`compute_c_hash`].
+///
+/// `alg` must be the algorithm the ID token was signed with.
+#[cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa")))
+)]
+pub fn validate_c_hash(
+    claims: &Value,
+    code: &str,
+    alg: SigningAlgorithm,
+) -> Result<(), IdTokenError> {
+    validate_hash(claims, "c_hash", code, alg)
+}
+
+#[cfg(any(feature = "hmac-sha2", feature = "rsa-pkcs1", feature = "ecdsa"))]
+fn validate_hash(
+    claims: &Value,
+    claim: &'static str,
+    value: &str,
+    alg: SigningAlgorithm,
+) -> Result<(), IdTokenError> {
+    let expected = compute_at_hash(value, alg).ok_or(IdTokenError::UnsupportedAlgorithm(alg))?;
+    let actual = claims.get(claim).ok_or(IdTokenError::MissingClaim(claim))?;
+    let actual = actual.as_str().ok_or(ClaimsError::InvalidClaim(claim))?;
+    if actual != expected {
+        return Err(IdTokenError::HashMismatch(claim));
+    }
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdTokenError {
+    #[error("the ID token has no `{0}` claim")]
+    MissingClaim(&'static str),
+    #[error("the ID token was not issued by the expected issuer")]
+    IssuerMismatch,
+    #[error("the ID token is not intended for this client")]
+    AudienceMismatch,
+    #[error("the ID token's authorized party is not this client")]
+    AuthorizedPartyMismatch,
+    #[error("the ID token's nonce does not match")]
+    NonceMismatch,
+    #[error("the ID token's `{0}` claim does not match")]
+    HashMismatch(&'static str),
+    #[error("the algorithm {0} has no hash defined for `at_hash` or `c_hash`")]
+    UnsupportedAlgorithm(SigningAlgorithm),
+    #[error(transparent)]
+    Claims(#[from] ClaimsError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const NOW: u64 = 1311280970;
+
+    fn opts() -> IdTokenOpts {
+        IdTokenOpts::new("https://server.example.com", "s6BhdRkqt3")
+    }
+
+    fn claims() -> Value {
+        json!({
+            "iss": "https://server.example.com",
+            "sub": "24400320",
+            "aud": "s6BhdRkqt3",
+            "exp": NOW + 600,
+            "iat": NOW,
+        })
+    }
+
+    #[test]
+    fn valid() {
+        assert!(validate_id_token_at(&claims(), &opts(), NOW).is_ok());
+
+        let mut claims = claims();
+        claims["exp"] = json!(NOW);
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts(), NOW),
+            Err(IdTokenError::Claims(ClaimsError::Expired))
+        ));
+    }
+
+    #[test]
+    fn required_claims() {
+        for claim in ["iss", "sub", "aud", "exp", "iat"] {
+            let mut claims = claims();
+            claims.as_object_mut().unwrap().remove(claim);
+            assert!(
+                matches!(
+                    validate_id_token_at(&claims, &opts(), NOW),
+                    Err(IdTokenError::MissingClaim(missing)) if missing == claim
+                ),
+                "{claim}"
+            );
+        }
+
+        let mut claims = claims();
+        claims["iss"] = json!("https://other.example.com");
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts(), NOW),
+            Err(IdTokenError::IssuerMismatch)
+        ));
+    }
+
+    #[test]
+    fn multiple_audiences_azp() {
+        let mut claims = claims();
+        claims["aud"] = json!(["s6BhdRkqt3", "other-client"]);
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts(), NOW),
+            Err(IdTokenError::MissingClaim("azp"))
+        ));
+
+        claims["azp"] = json!("other-client");
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts(), NOW),
+            Err(IdTokenError::AuthorizedPartyMismatch)
+        ));
+
+        claims["azp"] = json!("s6BhdRkqt3");
+        assert!(validate_id_token_at(&claims, &opts(), NOW).is_ok());
+
+        claims["aud"] = json!(["other-client", "another-client"]);
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts(), NOW),
+            Err(IdTokenError::AudienceMismatch)
+        ));
+    }
+
+    #[test]
+    fn nonce() {
+        let opts = opts().with_nonce("n-0S6_WzA2Mj");
+        let mut claims = claims();
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts, NOW),
+            Err(IdTokenError::MissingClaim("nonce"))
+        ));
+
+        claims["nonce"] = json!("n-0S6_WzA2Mk");
+        assert!(matches!(
+            validate_id_token_at(&claims, &opts, NOW),
+            Err(IdTokenError::NonceMismatch)
+        ));
+
+        claims["nonce"] = json!("n-0S6_WzA2Mj");
+        assert!(validate_id_token_at(&claims, &opts, NOW).is_ok());
+    }
+
+    /// The examples from [appendix A.3 and A.4 of OpenID Connect Core 1.0](https://openid.net/specs/openid-connect-core-1_0.html#id_tokenExample),
+    /// which are signed with `RS256`.
+    #[test]
+    #[cfg(feature = "rsa-pkcs1")]
+    fn token_hashes() {
+        let access_token = "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y";
+        let code = "Qcb0Orv1zh30vL1MPRsbm-diHiMwcLyZvn1arpZv-Jxf_11jnpEX3Tgfvk";
+        let alg = SigningAlgorithm::RS256;
+        assert_eq!(
+            compute_at_hash(access_token, alg).as_deref(),
+            Some("77QmUPtjPfzWtF2AnpK9RQ")
+        );
+        assert_eq!(
+            compute_c_hash(code, alg).as_deref(),
+            Some("LDktKdoQak3Pk0cnXxCltA")
+        );
+
+        let claims =
+            json!({ "at_hash": "77QmUPtjPfzWtF2AnpK9RQ", "c_hash": "LDktKdoQak3Pk0cnXxCltA" });
+        assert!(validate_at_hash(&claims, access_token, alg).is_ok());
+        assert!(validate_c_hash(&claims, code, alg).is_ok());
+        assert!(matches!(
+            validate_at_hash(&claims, code, alg),
+            Err(IdTokenError::HashMismatch("at_hash"))
+        ));
+        assert!(matches!(
+            validate_at_hash(&json!({}), access_token, alg),
+            Err(IdTokenError::MissingClaim("at_hash"))
+        ));
+
+        // The hash follows the algorithm.
+        assert_eq!(
+            compute_at_hash(access_token, SigningAlgorithm::RS512)
+                .unwrap()
+                .len(),
+            43
+        );
+        assert!(validate_at_hash(&claims, access_token, SigningAlgorithm::RS384).is_err());
+    }
+}
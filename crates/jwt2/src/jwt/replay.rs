@@ -0,0 +1,228 @@
+//! Replay protection based on the `jti` (JWT ID) claim.
+//!
+//! A valid token can be used any number of times until it expires. If that's undesirable,
+//! e.g. for one-time tokens, remember the `jti` of each token you accept and reject tokens whose
+//! `jti` you've seen before. `jwt2` doesn't care where that's stored; implement [`ReplayGuard`]
+//! for your storage of choice, or use [`InMemoryReplayGuard`] if a single process is enough.
+//!
+//! See [`RawJwt::verify_once`](crate::jwt::RawJwt::verify_once).
+
+use crate::jwt::claims;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Storage for the IDs of tokens that have already been accepted.
+pub trait ReplayGuard {
+    /// Records `jti` as used, or fails with [`ReplayError::Replayed`] if it already was.
+    ///
+    /// `exp` is the expiry of the token (in seconds since the Unix epoch), including any leeway,
+    /// after which the token is rejected anyway, so the ID doesn't need to be remembered any
+    /// longer than that.
+    /// Checking and recording must happen atomically, otherwise two concurrent requests could
+    /// both use the same token.
+    fn check_and_record(&self, jti: &str, exp: u64) -> Result<(), ReplayError>;
+}
+
+impl<T> ReplayGuard for &T
+where
+    T: ?Sized + ReplayGuard,
+{
+    fn check_and_record(&self, jti: &str, exp: u64) -> Result<(), ReplayError> {
+        T::check_and_record(self, jti, exp)
+    }
+}
+
+/// Checks `claims` against `guard`.
+///
+/// Tokens without a `jti` are rejected. Tokens without an `exp` are remembered forever.
+/// `exp_leeway` should be the leeway the token's `exp` was validated with (see
+/// [`ValidationOptions::effective_exp_leeway`](crate::jwt::ValidationOptions::effective_exp_leeway)),
+/// since the token keeps being accepted, and so has to be remembered, until `exp` plus the
+/// leeway.
+pub fn check_claims<Guard>(
+    guard: &Guard,
+    claims: &Value,
+    exp_leeway: Duration,
+) -> Result<(), ReplayError>
+where
+    Guard: ?Sized + ReplayGuard,
+{
+    let jti = claims
+        .get("jti")
+        .and_then(Value::as_str)
+        .ok_or(ReplayError::MissingJti)?;
+    let exp = claims
+        .get("exp")
+        .and_then(claims::numeric_date)
+        .map_or(u64::MAX, |exp| exp.saturating_add(exp_leeway.as_secs()));
+    guard.check_and_record(jti, exp)
+}
+
+/// A [`ReplayGuard`] keeping the IDs in memory.
+///
+/// IDs are forgotten once their token has expired. Since nothing is shared between processes,
+/// this is only suitable for a single instance of a service (or for tests).
+#[derive(Debug, Default)]
+pub struct InMemoryReplayGuard {
+    // Maps each ID to the expiry of its token.
+    seen: Mutex<HashMap<String, u64>>,
+}
+
+impl InMemoryReplayGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like [`ReplayGuard::check_and_record`], with `now` (in seconds since the Unix epoch) as
+    /// the current time.
+    pub fn check_and_record_at(&self, jti: &str, exp: u64, now: u64) -> Result<(), ReplayError> {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        seen.retain(|_, expires| *expires > now);
+        if seen.contains_key(jti) {
+            return Err(ReplayError::Replayed);
+        }
+        seen.insert(jti.to_string(), exp);
+        Ok(())
+    }
+
+    /// The number of IDs currently remembered.
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ReplayGuard for InMemoryReplayGuard {
+    fn check_and_record(&self, jti: &str, exp: u64) -> Result<(), ReplayError> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs());
+        self.check_and_record_at(jti, exp, now)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("the token has already been used")]
+    Replayed,
+    #[error("the token has no `jti` claim")]
+    MissingJti,
+    #[error("could not check the token ID: {0}")]
+    Storage(Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn in_memory_rejects_repeats() {
+        let guard = InMemoryReplayGuard::new();
+        assert!(guard.check_and_record_at("a", 100, 50).is_ok());
+        assert!(guard.check_and_record_at("b", 200, 50).is_ok());
+        assert!(matches!(
+            guard.check_and_record_at("a", 100, 60),
+            Err(ReplayError::Replayed)
+        ));
+
+        // Once the token has expired, its ID is forgotten.
+        assert!(guard.check_and_record_at("c", 300, 100).is_ok());
+        assert_eq!(guard.len(), 2);
+    }
+
+    #[test]
+    fn check_claims_requires_jti() {
+        let guard = InMemoryReplayGuard::new();
+        let claims = json!({ "jti": "abc", "exp": u64::MAX });
+        assert!(check_claims(&guard, &claims, Duration::ZERO).is_ok());
+        assert!(matches!(
+            check_claims(&guard, &claims, Duration::ZERO),
+            Err(ReplayError::Replayed)
+        ));
+        assert!(matches!(
+            check_claims(&guard, &json!({ "exp": u64::MAX }), Duration::ZERO),
+            Err(ReplayError::MissingJti)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "hmac-sha2")]
+    fn verify_once() {
+        use crate::jwt::{Jwt, RawJwt, ValidationOptions, VerifyError};
+        use crate::sign::HS256;
+
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let token = Jwt::builder()
+            .claim("jti", "token-1")
+            .claim("exp", u64::MAX / 2)
+            .sign(&hs256)
+            .unwrap();
+        let jwt = RawJwt::decode(&token).unwrap();
+        let (guard, options) = (InMemoryReplayGuard::new(), ValidationOptions::default());
+
+        assert!(jwt.verify_once(&hs256, &options, &guard).is_ok());
+        assert!(matches!(
+            jwt.verify_once(&hs256, &options, &guard),
+            Err(VerifyError::Replay(ReplayError::Replayed))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "hmac-sha2")]
+    fn verify_once_with_leeway() {
+        use crate::jwt::{Jwt, RawJwt, ValidationOptions, VerifyError};
+        use crate::sign::HS256;
+
+        struct Recording(Mutex<Vec<u64>>);
+        impl ReplayGuard for Recording {
+            fn check_and_record(&self, _jti: &str, exp: u64) -> Result<(), ReplayError> {
+                self.0.lock().unwrap().push(exp);
+                Ok(())
+            }
+        }
+
+        // Expired, but still within the leeway.
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let token = Jwt::builder()
+            .claim("jti", "token-1")
+            .claim("exp", now - 30)
+            .sign(&hs256)
+            .unwrap();
+        let jwt = RawJwt::decode(&token).unwrap();
+        let options = ValidationOptions {
+            leeway: Duration::from_secs(60),
+            ..ValidationOptions::default()
+        };
+
+        // The guard has to remember the ID for as long as the token is accepted.
+        let recording = Recording(Mutex::new(Vec::new()));
+        assert!(jwt.verify_once(&hs256, &options, &recording).is_ok());
+        assert_eq!(*recording.0.lock().unwrap(), [now + 30]);
+
+        let guard = InMemoryReplayGuard::new();
+        assert!(jwt.verify_once(&hs256, &options, &guard).is_ok());
+        assert!(matches!(
+            jwt.verify_once(&hs256, &options, &guard),
+            Err(VerifyError::Replay(ReplayError::Replayed))
+        ));
+
+        // A separate exp leeway takes precedence.
+        let options = ValidationOptions {
+            exp_leeway: Some(Duration::from_secs(120)),
+            ..options
+        };
+        let recording = Recording(Mutex::new(Vec::new()));
+        assert!(jwt.verify_once(&hs256, &options, &recording).is_ok());
+        assert_eq!(*recording.0.lock().unwrap(), [now + 90]);
+    }
+}
@@ -0,0 +1,406 @@
+//! Validation of the contents of a JWT beyond its signature.
+//!
+//! Signature verification only tells you that a token was issued by someone holding the key;
+//! whether the token should actually be *accepted* is a policy decision, which is what
+//! [`ValidationOptions`] describes.
+
+use crate::jwt::{claims, VerifyError};
+use crate::{Header, SigningAlgorithm};
+use serde_json::Value;
+use std::time::{Duration, SystemTime};
+
+/// Options describing what a JWT must look like to be accepted.
+///
+/// By default, tokens must have an `exp` claim and not have expired, and may not be used before
+/// their `nbf` claim (if any), but nothing else is checked, so you'll probably want to set some of the fields.
+///
+/// ```
+/// use jwt2::jwt::ValidationOptions;
+/// use serde_json::json;
+///
+/// let options = ValidationOptions {
+///     required_scopes: vec!["read".to_string()],
+///     require_exp: false,
+///     ..Default::default()
+/// };
+/// assert!(options.validate_claims(&json!({ "scope": "read write" })).is_ok());
+/// assert!(options.validate_claims(&json!({ "scope": "write" })).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// The only algorithms that tokens may be signed with, regardless of what the verifier
+    /// supports.
+    ///
+    /// This is checked before any cryptographic operations take place, and is the recommended
+    /// mitigation for algorithm substitution attacks.
+    /// `None` allows any algorithm the verifier accepts.
+    pub allowed_algorithms: Option<Vec<SigningAlgorithm>>,
+
+    /// The media type that the `typ` header parameter must be, e.g. `at+jwt` for
+    /// [RFC 9068](https://www.rfc-editor.org/rfc/rfc9068.html) access tokens.
+    ///
+    /// This prevents tokens of one kind (such as ID tokens) from being accepted as another.
+    /// See [`Header::has_type`] for how types are compared. Tokens without a `typ` are rejected.
+    pub expected_typ: Option<String>,
+
+    /// Scopes that the token must grant.
+    ///
+    /// The granted scopes are read from the `scope` and `scp` claims;
+    /// see [`claims::parse_scopes`] for the accepted representations.
+    pub required_scopes: Vec<String>,
+
+    /// The maximum lifetime of a token, i.e. the largest allowed difference between
+    /// the `exp` and `iat` claims.
+    ///
+    /// This is a policy check against issuers minting absurdly long-lived tokens,
+    /// and is unrelated to whether the token has expired.
+    /// If set, both `iat` and `exp` must be present.
+    pub max_lifetime: Option<Duration>,
+
+    /// Whether tokens without an `exp` claim are rejected. Defaults to `true`.
+    ///
+    /// If this is `false`, tokens without `exp` never expire. Tokens that do have `exp` are
+    /// always checked against it either way.
+    pub require_exp: bool,
+
+    /// How long after `exp` a token is still accepted, and how long before `nbf` it already is,
+    /// to account for clock skew. Defaults to zero.
+    ///
+    /// See [`ValidationOptions::exp_leeway`] and [`ValidationOptions::nbf_leeway`] to use
+    /// different leeways for each.
+    pub leeway: Duration,
+
+    /// The leeway for `exp` only, overriding [`ValidationOptions::leeway`] if set.
+    pub exp_leeway: Option<Duration>,
+
+    /// The leeway for `nbf` only, overriding [`ValidationOptions::leeway`] if set.
+    ///
+    /// For example, a generous `nbf_leeway` with a zero `exp_leeway` accounts for issuers with
+    /// clocks running ahead, without accepting any expired tokens.
+    pub nbf_leeway: Option<Duration>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            allowed_algorithms: None,
+            expected_typ: None,
+            required_scopes: Vec::new(),
+            max_lifetime: None,
+            require_exp: true,
+            leeway: Duration::ZERO,
+            exp_leeway: None,
+            nbf_leeway: None,
+        }
+    }
+}
+
+impl ValidationOptions {
+    /// Creates the default options. Equivalent to [`ValidationOptions::default`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks that `header` conforms to these options.
+    pub fn validate_header(&self, header: &Header) -> Result<(), VerifyError> {
+        if let Some(ref allowed) = self.allowed_algorithms {
+            if !allowed.iter().any(|alg| header.algorithm == *alg) {
+                return Err(VerifyError::AlgorithmNotAllowed(header.algorithm));
+            }
+        }
+        if let Some(ref expected) = self.expected_typ {
+            if !header.has_type(expected) {
+                return Err(VerifyError::UnexpectedType(header.obj_type.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The leeway actually applied to `exp`, i.e. [`ValidationOptions::exp_leeway`] falling
+    /// back to [`ValidationOptions::leeway`].
+    pub fn effective_exp_leeway(&self) -> Duration {
+        self.exp_leeway.unwrap_or(self.leeway)
+    }
+
+    /// Checks that `claims` conform to these options, using the system clock as the
+    /// current time.
+    pub fn validate_claims(&self, claims: &Value) -> Result<(), ClaimsError> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs());
+        self.validate_claims_at(claims, now)
+    }
+
+    /// Checks that `claims` conform to these options, with `now` (in seconds since the Unix
+    /// epoch) as the current time.
+    pub fn validate_claims_at(&self, claims: &Value, now: u64) -> Result<(), ClaimsError> {
+        if !claims.is_object() {
+            return Err(ClaimsError::NotAnObject);
+        }
+
+        match claims.get("exp") {
+            Some(exp) => {
+                let exp = claims::numeric_date(exp).ok_or(ClaimsError::InvalidClaim("exp"))?;
+                // The token must be used *before* `exp`.
+                if now >= exp.saturating_add(self.effective_exp_leeway().as_secs()) {
+                    return Err(ClaimsError::Expired);
+                }
+            }
+            None if self.require_exp => return Err(ClaimsError::MissingExp),
+            None => {}
+        }
+
+        if let Some(nbf) = claims.get("nbf") {
+            let nbf = claims::numeric_date(nbf).ok_or(ClaimsError::InvalidClaim("nbf"))?;
+            let leeway = self.nbf_leeway.unwrap_or(self.leeway);
+            if now < nbf.saturating_sub(leeway.as_secs()) {
+                return Err(ClaimsError::NotYetValid);
+            }
+        }
+
+        if !self.required_scopes.is_empty() {
+            let granted = claims::granted_scopes(claims);
+            if let Some(missing) = self
+                .required_scopes
+                .iter()
+                .find(|required| !granted.contains(required))
+            {
+                return Err(ClaimsError::MissingScope(missing.clone()));
+            }
+        }
+
+        if let Some(max_lifetime) = self.max_lifetime {
+            let iat = claims.get("iat").ok_or(ClaimsError::MissingIat)?;
+            let iat = claims::numeric_date(iat).ok_or(ClaimsError::InvalidClaim("iat"))?;
+            let exp = claims.get("exp").ok_or(ClaimsError::MissingExp)?;
+            let exp = claims::numeric_date(exp).ok_or(ClaimsError::InvalidClaim("exp"))?;
+
+            if exp.saturating_sub(iat) > max_lifetime.as_secs() {
+                return Err(ClaimsError::LifetimeTooLong);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClaimsError {
+    #[error("the claims are not a JSON object")]
+    NotAnObject,
+    #[error("the token does not grant the required scope `{0}`")]
+    MissingScope(String),
+    #[error("the `{0}` claim is not a valid value")]
+    InvalidClaim(&'static str),
+    #[error("the token has no `iat` claim")]
+    MissingIat,
+    #[error("the token has no `exp` claim")]
+    MissingExp,
+    #[error("the token's lifetime is longer than allowed")]
+    LifetimeTooLong,
+    #[error("the token has expired")]
+    Expired,
+    #[error("the token is not valid yet")]
+    NotYetValid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn requiring(scopes: &[&str]) -> ValidationOptions {
+        ValidationOptions {
+            required_scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            require_exp: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn required_scopes_string() {
+        let options = requiring(&["read", "write"]);
+        assert!(options
+            .validate_claims(&json!({ "scope": "write read" }))
+            .is_ok());
+    }
+
+    #[test]
+    fn required_scopes_array() {
+        let options = requiring(&["read", "write"]);
+        assert!(options
+            .validate_claims(&json!({ "scp": ["read", "write", "admin"] }))
+            .is_ok());
+    }
+
+    #[test]
+    fn required_scopes_missing() {
+        let options = requiring(&["read", "admin"]);
+        let result = options.validate_claims(&json!({ "scope": "read write" }));
+        assert!(matches!(result, Err(ClaimsError::MissingScope(scope)) if scope == "admin"));
+
+        let result = options.validate_claims(&json!({ "sub": "1234567890" }));
+        assert!(matches!(result, Err(ClaimsError::MissingScope(scope)) if scope == "read"));
+    }
+
+    #[test]
+    fn claims_not_object() {
+        let options = ValidationOptions::new();
+        assert!(matches!(
+            options.validate_claims(&json!(["read"])),
+            Err(ClaimsError::NotAnObject)
+        ));
+    }
+
+    #[test]
+    fn max_lifetime() {
+        const HOUR: u64 = 60 * 60;
+        const YEAR: u64 = 365 * 24 * HOUR;
+        let options = ValidationOptions {
+            max_lifetime: Some(Duration::from_secs(HOUR)),
+            ..Default::default()
+        };
+
+        let iat = 1516239022;
+        assert!(options
+            .validate_claims_at(&json!({ "iat": iat, "exp": iat + HOUR }), iat)
+            .is_ok());
+        assert!(matches!(
+            options.validate_claims_at(&json!({ "iat": iat, "exp": iat + YEAR }), iat),
+            Err(ClaimsError::LifetimeTooLong)
+        ));
+        assert!(matches!(
+            options.validate_claims_at(&json!({ "exp": iat + HOUR }), iat),
+            Err(ClaimsError::MissingIat)
+        ));
+        assert!(matches!(
+            options.validate_claims_at(&json!({ "iat": iat }), iat),
+            Err(ClaimsError::MissingExp)
+        ));
+        assert!(options
+            .validate_claims_at(
+                &json!({ "iat": iat as f64 + 0.7, "exp": iat as f64 + HOUR as f64 + 0.2 }),
+                iat
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn require_exp() {
+        let now = 1516239022;
+        let claims = json!({ "sub": "1234567890", "iat": now });
+
+        let options = ValidationOptions::default();
+        assert!(options.require_exp);
+        assert!(matches!(
+            options.validate_claims_at(&claims, now),
+            Err(ClaimsError::MissingExp)
+        ));
+
+        let options = ValidationOptions {
+            require_exp: false,
+            ..Default::default()
+        };
+        assert!(options.validate_claims_at(&claims, now).is_ok());
+        assert!(options.validate_claims_at(&claims, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn expired() {
+        let exp = 1516239022;
+        let claims = json!({ "exp": exp });
+
+        for require_exp in [true, false] {
+            let options = ValidationOptions {
+                require_exp,
+                ..Default::default()
+            };
+            assert!(options.validate_claims_at(&claims, exp - 1).is_ok());
+            assert!(matches!(
+                options.validate_claims_at(&claims, exp),
+                Err(ClaimsError::Expired)
+            ));
+        }
+
+        let options = ValidationOptions {
+            leeway: Duration::from_secs(60),
+            ..Default::default()
+        };
+        assert!(options.validate_claims_at(&claims, exp + 59).is_ok());
+        assert!(options.validate_claims_at(&claims, exp + 60).is_err());
+        assert!(matches!(
+            options.validate_claims_at(&json!({ "exp": "tomorrow" }), exp),
+            Err(ClaimsError::InvalidClaim("exp"))
+        ));
+    }
+
+    #[test]
+    fn expected_typ() {
+        use crate::Algorithm;
+
+        let options = ValidationOptions {
+            expected_typ: Some("at+jwt".to_string()),
+            ..Default::default()
+        };
+        let mut header = Header::new(Algorithm::None);
+        header.obj_type = Some("application/at+jwt".to_string());
+        assert!(options.validate_header(&header).is_ok());
+
+        header.obj_type = Some("JWT".to_string());
+        assert!(matches!(
+            options.validate_header(&header),
+            Err(VerifyError::UnexpectedType(Some(ref typ))) if typ == "JWT"
+        ));
+        header.obj_type = None;
+        assert!(matches!(
+            options.validate_header(&header),
+            Err(VerifyError::UnexpectedType(None))
+        ));
+        assert!(ValidationOptions::default().validate_header(&header).is_ok());
+    }
+
+    #[test]
+    fn split_leeway() {
+        let now = 1516239022;
+        let options = ValidationOptions {
+            leeway: Duration::from_secs(30),
+            exp_leeway: Some(Duration::ZERO),
+            nbf_leeway: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+
+        // Within the nbf leeway.
+        let claims = json!({ "nbf": now + 60, "exp": now + 120 });
+        assert!(options.validate_claims_at(&claims, now).is_ok());
+        let claims = json!({ "nbf": now + 61, "exp": now + 120 });
+        assert!(matches!(
+            options.validate_claims_at(&claims, now),
+            Err(ClaimsError::NotYetValid)
+        ));
+
+        // No grace period for exp, even though the combined leeway would allow it.
+        let claims = json!({ "exp": now });
+        assert!(matches!(
+            options.validate_claims_at(&claims, now),
+            Err(ClaimsError::Expired)
+        ));
+
+        // Unset leeways fall back to the combined one.
+        let options = ValidationOptions {
+            nbf_leeway: None,
+            exp_leeway: None,
+            ..options
+        };
+        assert!(options.validate_claims_at(&claims, now).is_ok());
+        let claims = json!({ "nbf": now + 31, "exp": now + 120 });
+        assert!(matches!(
+            options.validate_claims_at(&claims, now),
+            Err(ClaimsError::NotYetValid)
+        ));
+        assert!(matches!(
+            options.validate_claims_at(&json!({ "nbf": "soon", "exp": now + 120 }), now),
+            Err(ClaimsError::InvalidClaim("nbf"))
+        ));
+    }
+}
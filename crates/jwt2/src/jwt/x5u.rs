@@ -0,0 +1,243 @@
+//! Verification of tokens using the `x5u` (X.509 URL) header parameter.
+//!
+//! The `x5u` header points to a PEM-encoded certificate chain whose first certificate holds
+//! the key the token was signed with
+//! (see [section 4.1.5 of RFC 7515](https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.5)).
+//!
+//! `jwt2` doesn't do any networking or X.509 processing itself.
+//! Instead, you implement [`X5uResolver`] to fetch and validate the chain, and [`X5uVerifier`]
+//! takes care of the rest.
+//!
+//! ## Security considerations
+//! The URL comes from the token, which is attacker-controlled until it has been verified.
+//! Fetching arbitrary URLs lets anyone make your server send requests wherever they like
+//! (server-side request forgery), so [`X5uVerifier`] only ever passes URLs matching its
+//! allowlist to the resolver.
+//! Validating the chain against your trust anchors is the resolver's job; a resolver that
+//! doesn't do so makes the signature worthless, since anyone can host a certificate.
+
+use crate::jwt::RawJwt;
+use crate::{JwsVerifier, ValidateHeaderParams};
+
+/// Fetches and validates the certificate chain an `x5u` header points to.
+pub trait X5uResolver {
+    /// The verifier for the key in the leaf certificate.
+    type Verifier: JwsVerifier;
+    /// The error returned when the chain can't be fetched or isn't valid.
+    type Error;
+
+    /// Fetches the certificate chain at `url`, validates it, and returns a verifier for the key
+    /// of the first (leaf) certificate.
+    ///
+    /// `url` has already been checked against the [`X5uVerifier`]'s allowlist.
+    fn resolve(&self, url: &str) -> Result<Self::Verifier, Self::Error>;
+}
+
+/// Verifies tokens using the key from the certificate chain their `x5u` header points to.
+///
+/// ```
+/// # #[cfg(feature = "hmac-sha2")] {
+/// use jwt2::jwt::x5u::{X5uResolver, X5uVerifier};
+/// use jwt2::sign::HS256;
+///
+/// struct Resolver;
+/// impl X5uResolver for Resolver {
+///     type Verifier = HS256;
+///     type Error = std::io::Error;
+///     fn resolve(&self, url: &str) -> Result<HS256, std::io::Error> {
+///         // Fetch the chain, validate it and build a verifier from its key here.
+///         // This one can't fetch anything, so every token is rejected.
+///         Err(std::io::Error::new(
+///             std::io::ErrorKind::Unsupported,
+///             format!("not fetching {url}"),
+///         ))
+///     }
+/// }
+///
+/// let verifier = X5uVerifier::new(Resolver).allow("https://certs.example.com/");
+/// assert!(verifier.is_allowed("https://certs.example.com/signing.pem"));
+/// assert!(!verifier.is_allowed("https://certs.example.com.evil.net/signing.pem"));
+/// # }
+/// ```
+pub struct X5uVerifier<Resolver> {
+    pub resolver: Resolver,
+    /// The URL prefixes that may be passed to the resolver.
+    ///
+    /// See [`X5uVerifier::is_allowed`] for how these are matched.
+    pub allowed_urls: Vec<String>,
+}
+
+impl<Resolver> X5uVerifier<Resolver>
+where
+    Resolver: X5uResolver,
+{
+    /// Creates a verifier with an empty allowlist, which rejects every URL.
+    pub fn new(resolver: Resolver) -> Self {
+        Self {
+            resolver,
+            allowed_urls: Vec::new(),
+        }
+    }
+
+    /// Adds a URL prefix to the allowlist.
+    pub fn allow(mut self, prefix: impl Into<String>) -> Self {
+        self.allowed_urls.push(prefix.into());
+        self
+    }
+
+    /// Checks whether `url` may be passed to the resolver.
+    ///
+    /// A URL is allowed if it uses HTTPS and either equals one of the allowed prefixes, or
+    /// starts with one and continues with a new path segment.
+    /// This means that `https://example.com` allows `https://example.com/certs.pem`, but not
+    /// `https://example.com.evil.net/certs.pem`.
+    pub fn is_allowed(&self, url: &str) -> bool {
+        if !url.starts_with("https://") {
+            return false;
+        }
+        self.allowed_urls
+            .iter()
+            .any(|prefix| match url.strip_prefix(prefix.as_str()) {
+                Some(rest) => rest.is_empty() || prefix.ends_with('/') || rest.starts_with('/'),
+                None => false,
+            })
+    }
+
+    /// Verifies the signature of `jwt` using the key from the certificate chain its `x5u`
+    /// header points to.
+    ///
+    /// Like [`RawJwt::verify_signature`], this doesn't look at the claims.
+    pub fn verify_signature(&self, jwt: &RawJwt) -> Result<(), X5uError<Resolver::Error>> {
+        let url = jwt.header.x5u.as_deref().ok_or(X5uError::MissingUrl)?;
+        if !self.is_allowed(url) {
+            return Err(X5uError::UrlNotAllowed(url.to_string()));
+        }
+
+        let verifier = self.resolver.resolve(url).map_err(X5uError::Resolve)?;
+        let extensions_supported = jwt
+            .header
+            .supports_required_extensions_with(verifier.crit_handler());
+        if !extensions_supported || !verifier.validate_header(&jwt.header) {
+            return Err(X5uError::HeaderRejected);
+        }
+        if !verifier.verify_signature_with_header(
+            &jwt.header,
+            jwt.header_and_payload.as_bytes(),
+            &jwt.signature,
+        ) {
+            return Err(X5uError::SignatureInvalid);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum X5uError<ResolveError> {
+    #[error("the header has no `x5u` parameter")]
+    MissingUrl,
+    #[error("the `x5u` URL {0} is not allowed")]
+    UrlNotAllowed(String),
+    #[error("could not resolve the certificate chain: {0}")]
+    Resolve(ResolveError),
+    #[error("the header was rejected by the verifier")]
+    HeaderRejected,
+    #[error("the signature is invalid")]
+    SignatureInvalid,
+}
+
+#[cfg(all(test, feature = "ecdsa"))]
+mod tests {
+    use super::*;
+    use crate::sign::ecdsa::tests::{JWTIO_PRIVATE_KEY_ES256, JWTIO_PUBLIC_KEY_ES256};
+    use crate::sign::ecdsa::{ES256Public, ES256};
+    use crate::Header;
+    use std::cell::Cell;
+
+    const CERT_URL: &str = "https://certs.example.com/es256.pem";
+
+    /// Stands in for a resolver that fetches and validates a real chain.
+    /// It "fetches" the public key from jwt.io's ES256 example and counts how often it's asked.
+    struct MockResolver {
+        calls: Cell<usize>,
+    }
+    impl X5uResolver for MockResolver {
+        type Verifier = ES256Public;
+        type Error = &'static str;
+
+        fn resolve(&self, url: &str) -> Result<ES256Public, &'static str> {
+            self.calls.set(self.calls.get() + 1);
+            if url != CERT_URL {
+                return Err("not found");
+            }
+            ES256Public::parse_pem(JWTIO_PUBLIC_KEY_ES256).map_err(|_| "invalid certificate")
+        }
+    }
+
+    fn verifier() -> X5uVerifier<MockResolver> {
+        X5uVerifier::new(MockResolver {
+            calls: Cell::new(0),
+        })
+        .allow("https://certs.example.com")
+    }
+
+    fn token(x5u: Option<&str>) -> String {
+        let es256 = ES256::parse_pem(JWTIO_PRIVATE_KEY_ES256).expect("Could not decode key");
+        let mut header = Header::recommended(&es256);
+        header.x5u = x5u.map(str::to_string);
+        crate::jwt::Jwt::builder()
+            .header(header)
+            .claim("sub", "1234567890")
+            .sign(&es256)
+            .expect("Could not sign")
+    }
+
+    #[test]
+    fn verify_with_resolved_key() {
+        let verifier = verifier();
+        let token = token(Some(CERT_URL));
+        let jwt = RawJwt::decode(&token).unwrap();
+        assert!(verifier.verify_signature(&jwt).is_ok());
+        assert_eq!(verifier.resolver.calls.get(), 1);
+
+        // Same header and signature, but with `{}` as the payload.
+        let [header, _, signature] = jwt.segments();
+        let tampered = format!("{header}.e30.{signature}");
+        let jwt = RawJwt::decode(&tampered).unwrap();
+        assert!(matches!(
+            verifier.verify_signature(&jwt),
+            Err(X5uError::SignatureInvalid)
+        ));
+    }
+
+    #[test]
+    fn disallowed_urls_are_not_resolved() {
+        let verifier = verifier();
+        for url in [
+            "https://certs.example.com.evil.net/es256.pem",
+            "http://certs.example.com/es256.pem",
+            "https://169.254.169.254/latest/meta-data",
+        ] {
+            let token = token(Some(url));
+            let jwt = RawJwt::decode(&token).unwrap();
+            assert!(matches!(
+                verifier.verify_signature(&jwt),
+                Err(X5uError::UrlNotAllowed(_))
+            ));
+        }
+        assert_eq!(verifier.resolver.calls.get(), 0);
+
+        let token = token(None);
+        let jwt = RawJwt::decode(&token).unwrap();
+        assert!(matches!(
+            verifier.verify_signature(&jwt),
+            Err(X5uError::MissingUrl)
+        ));
+    }
+
+    #[test]
+    fn x5u_round_trips() {
+        let token = token(Some(CERT_URL));
+        let jwt = RawJwt::decode(&token).unwrap();
+        assert_eq!(jwt.header.x5u.as_deref(), Some(CERT_URL));
+    }
+}
@@ -1,5 +1,19 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(not(any(
+    feature = "hmac-sha2",
+    feature = "hmac-sha3",
+    feature = "rsa-pkcs1",
+    feature = "ecdsa",
+    feature = "eddsa",
+    feature = "no-default-algorithm"
+)))]
+compile_error!(
+    "jwt2 was built without any algorithms, so it can't sign or verify anything. \
+     Enable at least one of the `hmac-sha2`, `hmac-sha3`, `rsa-pkcs1`, `ecdsa` or `eddsa` \
+     features, or enable `no-default-algorithm` if this is intentional."
+);
+
 pub mod header;
 pub mod repr;
 pub mod sign;
@@ -7,10 +21,22 @@ pub mod sign;
 pub mod jwt;
 pub mod util;
 
-pub use header::{Header, Algorithm, ValidateHeaderParams, RecommendHeaderParams};
+#[cfg(feature = "jwk")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jwk")))]
+pub mod jwk;
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+pub mod axum;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
+pub use header::{Header, Algorithm, ValidateHeaderParams, RecommendHeaderParams, CritHandler};
 pub use sign::{JwsSigner, JwsVerifier, SigningAlgorithm};
 
-pub use jwt::JwtData;
+pub use jwt::{JwtData, ValidationOptions};
 pub use util::WithKeyId;
 
 // TODO: Crate-level documentation.
@@ -1,5 +1,7 @@
 use base64ct::Encoding;
 
+pub(crate) mod json;
+
 /// Encodes to the preferred base64 format specified by RFC 7515:
 ///
 /// > Base64 encoding using the URL- and filename-safe character set
@@ -14,6 +16,23 @@ pub fn encode_bytes_as_base64url(s: &[u8]) -> String {
     base64ct::Base64UrlUnpadded::encode_string(s)
 }
 
+/// Like [`encode_bytes_as_base64url`], but appends the encoded bytes to `out` instead of
+/// allocating a new string.
+///
+/// ```
+/// let mut token = String::from("header.payload.");
+/// jwt2::repr::append_bytes_as_base64url(b"signature", &mut token);
+/// assert_eq!(token, "header.payload.c2lnbmF0dXJl");
+/// ```
+pub fn append_bytes_as_base64url(s: &[u8], out: &mut String) {
+    let mut bytes = core::mem::take(out).into_bytes();
+    let start = bytes.len();
+    bytes.resize(start + base64ct::Base64UrlUnpadded::encoded_len(s), 0);
+    base64ct::Base64UrlUnpadded::encode(s, &mut bytes[start..])
+        .expect("The buffer has exactly the encoded length");
+    *out = String::from_utf8(bytes).expect("base64 is always valid UTF-8");
+}
+
 /// Decodes bytes from the preferred base64 format specified by RFC 7515:
 ///
 /// > Base64 encoding using the URL- and filename-safe character set
@@ -30,6 +49,43 @@ pub fn decode_bytes_from_base64url(s: &str) -> Result<Vec<u8>, base64ct::Error>
     base64ct::Base64UrlUnpadded::decode_vec(s)
 }
 
+/// Gets the length of `len` bytes once encoded as base64url (without padding).
+pub fn base64url_len(len: usize) -> usize {
+    // Every 3 bytes become 4 characters, and a partial group of 1 or 2 bytes becomes 2 or 3.
+    len / 3 * 4 + (len % 3 * 4).div_ceil(3)
+}
+
+/// Decodes bytes from base64url, accepting input both with and without trailing `=` padding.
+///
+/// **This is not strict.** RFC 7515 requires padding to be omitted, and
+/// [`decode_bytes_from_base64url`] rejects padded input accordingly.
+/// Only use this when interoperating with non-compliant producers that emit padding.
+pub fn decode_bytes_from_base64url_lenient(s: &str) -> Result<Vec<u8>, base64ct::Error> {
+    if s.ends_with('=') {
+        base64ct::Base64Url::decode_vec(s)
+    } else {
+        base64ct::Base64UrlUnpadded::decode_vec(s)
+    }
+}
+
+/// Decodes bytes from base64url like [`decode_bytes_from_base64url_lenient`], falling back to
+/// the standard base64 alphabet (with `+` and `/`) if that fails.
+///
+/// **This is not strict.** Some producers mistakenly use the standard alphabet for JWS
+/// segments; only use this to interoperate with them. Input mixing both alphabets is rejected.
+pub fn decode_bytes_from_base64_any(s: &str) -> Result<Vec<u8>, base64ct::Error> {
+    decode_bytes_from_base64url_lenient(s).or_else(|e| {
+        if !s.contains(['+', '/']) {
+            return Err(e);
+        }
+        if s.ends_with('=') {
+            base64ct::Base64::decode_vec(s)
+        } else {
+            base64ct::Base64Unpadded::decode_vec(s)
+        }
+    })
+}
+
 /// Encodes a value as a base64-encoded JSON string.
 ///
 /// Effectively equivalent to the following:
@@ -46,6 +102,41 @@ where
     Ok(encode_bytes_as_base64url(&bytes))
 }
 
+/// Encodes a value as canonical JSON, i.e. with the keys of every object sorted and without any
+/// whitespace, so that equal values always give the same bytes regardless of key order.
+///
+/// Keys are sorted by their UTF-8 bytes. Note that this is not the full
+/// [JSON Canonicalization Scheme](https://www.rfc-editor.org/rfc/rfc8785.html): numbers and
+/// strings are written however `serde_json` writes them.
+///
+/// ```
+/// let value = serde_json::json!({ "b": 1, "a": { "d": [], "c": null } });
+/// let json = jwt2::repr::encode_value_as_canonical_json(&value).unwrap();
+/// assert_eq!(json, br#"{"a":{"c":null,"d":[]},"b":1}"#);
+/// ```
+pub fn encode_value_as_canonical_json<T>(value: &T) -> Result<Vec<u8>, serde_json::Error>
+where
+    T: serde::Serialize,
+{
+    // Rebuilding every object in order works whether or not `serde_json` preserves insertion
+    // order (which another crate may have turned on).
+    fn sort(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<_> = map.into_iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort(value)))
+                    .collect()
+            }
+            serde_json::Value::Array(values) => values.into_iter().map(sort).collect(),
+            other => other,
+        }
+    }
+    serde_json::to_vec(&sort(serde_json::to_value(value)?))
+}
+
 /// Decodes a value from a base64-encoded JSON string.
 ///
 /// Effectively equivalent to the following:
@@ -59,7 +150,80 @@ where
     T: serde::de::DeserializeOwned,
 {
     let decoded = decode_bytes_from_base64url(s).map_err(DecodeError::Base64)?;
-    Ok(serde_json::from_slice(&decoded)?)
+    json::from_vec(decoded)
+}
+
+/// Decodes a value from a base64-encoded JSON string using
+/// [`decode_bytes_from_base64url_lenient`].
+///
+/// **This is not strict.** Prefer [`decode_value_from_base64url`] where possible.
+pub fn decode_value_from_base64url_lenient<T>(s: &str) -> Result<T, DecodeError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let decoded = decode_bytes_from_base64url_lenient(s).map_err(DecodeError::Base64)?;
+    json::from_vec(decoded)
+}
+
+/// Decodes a value from a base64-encoded JSON string, rejecting JSON nested deeper than
+/// `max_depth` arrays and/or objects.
+///
+/// This is intended for untrusted input: the nesting depth is checked before the JSON is
+/// deserialised at all, so pathologically nested input is rejected without ever reaching
+/// a recursive deserialiser.
+///
+/// ```
+/// use jwt2::repr::{decode_value_from_base64url_limited, encode_bytes_as_base64url, DecodeError};
+///
+/// let shallow = encode_bytes_as_base64url(b"[[1]]");
+/// let value: serde_json::Value = decode_value_from_base64url_limited(&shallow, 2).unwrap();
+/// assert_eq!(value, serde_json::json!([[1]]));
+///
+/// let deep = encode_bytes_as_base64url(b"[[[1]]]");
+/// let result = decode_value_from_base64url_limited::<serde_json::Value>(&deep, 2);
+/// assert!(matches!(result, Err(DecodeError::TooDeep)));
+/// ```
+pub fn decode_value_from_base64url_limited<T>(s: &str, max_depth: usize) -> Result<T, DecodeError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let decoded = decode_bytes_from_base64url(s).map_err(DecodeError::Base64)?;
+    if json_depth_exceeds(&decoded, max_depth) {
+        return Err(DecodeError::TooDeep);
+    }
+    json::from_vec(decoded)
+}
+
+/// Checks whether the arrays and objects in `json` are nested deeper than `max_depth`.
+///
+/// This doesn't validate the JSON; that's left to the actual deserialiser.
+fn json_depth_exceeds(json: &[u8], max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in json {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                if depth > max_depth {
+                    return true;
+                }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -68,12 +232,26 @@ pub enum DecodeError {
     Base64(base64ct::Error),
     #[error("invalid json: {0}")]
     Json(#[from] serde_json::Error),
+    #[cfg(feature = "simd-json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "simd-json")))]
+    #[error("invalid json: {0}")]
+    SimdJson(simd_json::Error),
+    #[error("the json is nested too deeply")]
+    TooDeep,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn base64url_len_matches_encoding() {
+        for len in 0..32 {
+            let bytes = vec![0xAB; len];
+            assert_eq!(base64url_len(len), encode_bytes_as_base64url(&bytes).len());
+        }
+    }
+
     /// A test of encoding the example JWS Protected Header in
     /// [RFC 7515's Appendix A.1](https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A.1).
     #[test]
@@ -106,4 +284,57 @@ mod tests {
         let decoded = decode_bytes_from_base64url(&encoded).expect("Could not decode");
         assert_eq!(arr, decoded.as_slice())
     }
+
+    #[test]
+    fn limited_depth() {
+        let nested = |depth: usize| {
+            let json = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+            encode_bytes_as_base64url(json.as_bytes())
+        };
+
+        let value: serde_json::Value =
+            decode_value_from_base64url_limited(&nested(16), 16).expect("Could not decode");
+        assert!(value.is_array());
+
+        let result = decode_value_from_base64url_limited::<serde_json::Value>(&nested(100_000), 16);
+        assert!(matches!(result, Err(DecodeError::TooDeep)));
+    }
+
+    #[test]
+    fn limited_depth_ignores_strings() {
+        let json = br#"{"a":"[[[[{{{{\"]]]]","b":[1]}"#;
+        let encoded = encode_bytes_as_base64url(json);
+        let value: serde_json::Value =
+            decode_value_from_base64url_limited(&encoded, 2).expect("Could not decode");
+        assert_eq!(value["b"], serde_json::json!([1]));
+    }
+
+    #[test]
+    fn lenient_padding() {
+        // "hi" encodes to "aGk" unpadded, or "aGk=" padded.
+        assert!(decode_bytes_from_base64url("aGk=").is_err());
+        assert_eq!(
+            decode_bytes_from_base64url_lenient("aGk=").expect("Could not decode"),
+            b"hi"
+        );
+        assert_eq!(
+            decode_bytes_from_base64url_lenient("aGk").expect("Could not decode"),
+            b"hi"
+        );
+    }
+
+    #[test]
+    fn any_alphabet() {
+        // [0xfb, 0xff] encodes to "-_8" in base64url, or "+/8" in standard base64.
+        assert!(decode_bytes_from_base64url("+/8").is_err());
+        assert!(decode_bytes_from_base64url_lenient("+/8").is_err());
+        for encoded in ["+/8", "+/8=", "-_8", "-_8="] {
+            assert_eq!(
+                decode_bytes_from_base64_any(encoded).expect("Could not decode"),
+                [0xfb, 0xff],
+                "{encoded}"
+            );
+        }
+        assert!(decode_bytes_from_base64_any("-/8").is_err());
+    }
 }
@@ -0,0 +1,46 @@
+//! The JSON backend used when decoding.
+//!
+//! Everything in `jwt2` that parses JSON goes through here, so that the backend can be swapped
+//! out by features without the rest of the crate caring.
+//! By default this is `serde_json`; the `simd-json` feature switches to `simd-json`,
+//! which is considerably faster for large payloads.
+//!
+//! Encoding always uses `serde_json`.
+
+use super::DecodeError;
+use serde::de::{Deserialize, DeserializeOwned};
+
+/// Deserialises a value from JSON bytes.
+///
+/// This takes the buffer by value since `simd-json` parses in place.
+#[cfg(not(feature = "simd-json"))]
+#[inline]
+pub(crate) fn from_vec<T>(bytes: Vec<u8>) -> Result<T, DecodeError>
+where
+    T: DeserializeOwned,
+{
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Deserialises a value from JSON bytes.
+///
+/// This takes the buffer by value since `simd-json` parses in place.
+#[cfg(feature = "simd-json")]
+#[inline]
+pub(crate) fn from_vec<T>(mut bytes: Vec<u8>) -> Result<T, DecodeError>
+where
+    T: DeserializeOwned,
+{
+    simd_json::serde::from_slice(&mut bytes).map_err(DecodeError::SimdJson)
+}
+
+/// Deserialises a value that may borrow from `bytes`.
+///
+/// This always uses `serde_json`, since `simd-json` would have to modify the buffer.
+#[inline]
+pub(crate) fn from_slice<'de, T>(bytes: &'de [u8]) -> Result<T, DecodeError>
+where
+    T: Deserialize<'de>,
+{
+    Ok(serde_json::from_slice(bytes)?)
+}
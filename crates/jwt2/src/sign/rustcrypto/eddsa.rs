@@ -0,0 +1,150 @@
+//! # EdDSA ([`EdDSAPublic`])
+//!
+//! Only Ed25519 is supported, and only for verifying tokens, since that's what's needed to
+//! accept tokens from identity providers that publish Ed25519 keys.
+//! See [RFC 8037](https://www.rfc-editor.org/rfc/rfc8037.html).
+
+#[cfg(feature = "jwk")]
+use crate::jwk::{Jwk, JwkError, JwkKey};
+#[cfg(feature = "jwk")]
+use crate::repr;
+use crate::{JwsVerifier, SigningAlgorithm, ValidateHeaderParams};
+use ed25519_dalek::{Signature, SignatureError, VerifyingKey, PUBLIC_KEY_LENGTH};
+
+/// An Ed25519 public key, for verifying `EdDSA` tokens.
+#[derive(Clone)]
+pub struct EdDSAPublic {
+    key: VerifyingKey,
+}
+
+impl EdDSAPublic {
+    pub fn from(key: VerifyingKey) -> Self {
+        Self { key }
+    }
+    /// Constructs a key from its raw 32-byte encoding, e.g. the `x` parameter of a JWK.
+    ///
+    /// Fails if `bytes` isn't a valid point on the curve.
+    pub fn from_bytes(bytes: &[u8; PUBLIC_KEY_LENGTH]) -> Result<Self, SignatureError> {
+        VerifyingKey::from_bytes(bytes).map(Self::from)
+    }
+    /// The raw 32-byte encoding of this key.
+    pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_LENGTH] {
+        self.key.to_bytes()
+    }
+
+    /// Imports a key from a JWK with `"kty": "OKP"` and `"crv": "Ed25519"`.
+    ///
+    /// See [section 2 of RFC 8037](https://www.rfc-editor.org/rfc/rfc8037.html#section-2).
+    #[cfg(feature = "jwk")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "jwk")))]
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, JwkError> {
+        let JwkKey::Okp { crv, x } = &jwk.key else {
+            return Err(JwkError::WrongKeyType);
+        };
+        if crv != "Ed25519" {
+            return Err(JwkError::WrongCurve(crv.clone()));
+        }
+        let x = repr::decode_bytes_from_base64url(x).map_err(JwkError::Base64)?;
+        let x = x.as_slice().try_into().map_err(|_| JwkError::InvalidKey)?;
+        Self::from_bytes(x).map_err(|_| JwkError::InvalidKey)
+    }
+    /// Exports this key as a JWK, with `alg` set to `EdDSA`.
+    #[cfg(feature = "jwk")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "jwk")))]
+    pub fn to_jwk(&self) -> Jwk {
+        let mut jwk = Jwk::new(JwkKey::Okp {
+            crv: "Ed25519".to_string(),
+            x: repr::encode_bytes_as_base64url(&self.to_bytes()),
+        });
+        jwk.algorithm = Some(SigningAlgorithm::EdDSA.to_string());
+        jwk
+    }
+}
+
+impl From<VerifyingKey> for EdDSAPublic {
+    fn from(key: VerifyingKey) -> Self {
+        Self::from(key)
+    }
+}
+
+impl ValidateHeaderParams for EdDSAPublic {
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        alg == SigningAlgorithm::EdDSA
+    }
+}
+impl JwsVerifier for EdDSAPublic {
+    fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+        let Ok(signature) = Signature::from_slice(signature) else {
+            return false;
+        };
+        // The strict variant rejects weak keys and non-canonical signatures, which the plain
+        // one accepts for compatibility with some older implementations.
+        self.key.verify_strict(data, &signature).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jwt::RawJwt;
+
+    // The example from appendix A.4 of RFC 8037.
+    const PUBLIC_KEY: &str = "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo";
+    const TOKEN: &str = "eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc.hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6dWbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+
+    fn public_key_bytes() -> [u8; PUBLIC_KEY_LENGTH] {
+        crate::repr::decode_bytes_from_base64url(PUBLIC_KEY)
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn verify_with_raw_key() {
+        let public = EdDSAPublic::from_bytes(&public_key_bytes()).expect("Invalid key");
+        assert_eq!(public.to_bytes(), public_key_bytes());
+
+        let jwt = RawJwt::decode(TOKEN).expect("Could not decode");
+        assert_eq!(
+            jwt.header.algorithm,
+            crate::Algorithm::Signing(SigningAlgorithm::EdDSA)
+        );
+        assert!(jwt.verify_signature(&public));
+
+        let mut other = public_key_bytes();
+        other[0] ^= 1;
+        if let Ok(other) = EdDSAPublic::from_bytes(&other) {
+            assert!(!jwt.verify_signature(&other));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "jwk")]
+    fn verify_with_jwk() {
+        let jwk: Jwk = serde_json::from_str(&format!(
+            r#"{{ "kty": "OKP", "crv": "Ed25519", "x": "{PUBLIC_KEY}" }}"#
+        ))
+        .unwrap();
+        let public = jwk.to_eddsa_public().expect("Could not import JWK");
+        assert!(RawJwt::decode(TOKEN).unwrap().verify_signature(&public));
+
+        let mut exported = public.to_jwk();
+        assert_eq!(exported.algorithm.as_deref(), Some("EdDSA"));
+        exported.algorithm = None;
+        assert_eq!(exported, jwk);
+
+        let x448 = Jwk::new(JwkKey::Okp {
+            crv: "X448".to_string(),
+            x: PUBLIC_KEY.to_string(),
+        });
+        assert!(matches!(
+            x448.to_eddsa_public(),
+            Err(JwkError::WrongCurve(crv)) if crv == "X448"
+        ));
+        let short = Jwk::new(JwkKey::Okp {
+            crv: "Ed25519".to_string(),
+            x: PUBLIC_KEY[..40].to_string(),
+        });
+        assert!(matches!(short.to_eddsa_public(), Err(JwkError::InvalidKey)));
+    }
+}
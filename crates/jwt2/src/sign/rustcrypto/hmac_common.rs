@@ -0,0 +1,158 @@
+//! Code shared by the HMAC-based algorithms, regardless of the hash.
+
+use hmac::Mac;
+
+pub type ConstructError = hmac::digest::InvalidLength;
+
+/// An error creating an HMAC instance from a hex-encoded secret.
+#[derive(Debug, thiserror::Error)]
+pub enum FromHexError {
+    #[error("the secret is not valid hex")]
+    InvalidHex,
+    #[error(transparent)]
+    Construct(#[from] ConstructError),
+}
+
+/// Decodes a hex string, in either case, into bytes.
+pub(crate) fn decode_hex(s: &str) -> Result<Vec<u8>, FromHexError> {
+    fn nibble(c: u8) -> Result<u8, FromHexError> {
+        match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(FromHexError::InvalidHex),
+        }
+    }
+
+    let s = s.as_bytes();
+    if !s.len().is_multiple_of(2) {
+        return Err(FromHexError::InvalidHex);
+    }
+    s.chunks_exact(2)
+        .map(|pair| Ok(nibble(pair[0])? << 4 | nibble(pair[1])?))
+        .collect()
+}
+
+/// What an HMAC instance keeps around to compute MACs with.
+#[derive(Clone)]
+pub(crate) enum HmacKey<M> {
+    /// The hash states after absorbing the padded key, which is the cheapest to compute MACs
+    /// from, but isn't zeroized when dropped.
+    State(M),
+    /// The key itself, from which a fresh state is derived for every MAC.
+    #[cfg(feature = "zeroize")]
+    Zeroizing(zeroize::Zeroizing<Vec<u8>>),
+}
+impl<M> HmacKey<M>
+where
+    M: Mac + hmac::digest::KeyInit + Clone,
+{
+    pub(crate) fn new(key: &[u8]) -> Result<Self, ConstructError> {
+        Ok(Self::State(<M as Mac>::new_from_slice(key)?))
+    }
+
+    #[cfg(feature = "zeroize")]
+    pub(crate) fn new_zeroizing(
+        key: &zeroize::Zeroizing<Vec<u8>>,
+    ) -> Result<Self, ConstructError> {
+        // Only to report invalid keys up front; the state itself is thrown away.
+        <M as Mac>::new_from_slice(key)?;
+        Ok(Self::Zeroizing(key.clone()))
+    }
+
+    /// Returns a state that has absorbed the key but no data yet.
+    pub(crate) fn mac(&self) -> M {
+        match self {
+            Self::State(state) => state.clone(),
+            #[cfg(feature = "zeroize")]
+            Self::Zeroizing(key) => {
+                <M as Mac>::new_from_slice(key).expect("the key was accepted on construction")
+            }
+        }
+    }
+}
+
+// Expects `Algorithm`, `RecommendHeaderParams`, `ValidateHeaderParams`, `JwsSigner`,
+// `JwsVerifier`, `SigningAlgorithm`, `Hmac`, `Mac`, `ConstructError`, `FromHexError`, `HmacKey`
+// and `decode_hex` to be in scope.
+macro_rules! impl_hs {
+    ($struct_ident:ty: alg = $algorithm:expr, hash = $hash_ty:ty) => {
+        impl $struct_ident {
+            /// Creates an instance from a hex-encoded secret, e.g. `796f7572...`.
+            ///
+            /// The secret is the bytes the hex decodes to, not the hex string itself; passing
+            /// the same string to `new` would give a different key. Both upper- and lowercase
+            /// digits are accepted, but no whitespace or `0x` prefix.
+            pub fn from_hex(secret: &str) -> Result<Self, FromHexError> {
+                let key = decode_hex(secret)?;
+                Ok(Self::new(&key)?)
+            }
+
+            /// Creates an instance that keeps its own copy of the key in a [`Zeroizing`]
+            /// container, e.g. one loaded from a secret store.
+            ///
+            /// Unlike [`new`](Self::new), this doesn't keep any hash states derived from the key
+            /// around, so nothing that could be used to forge MACs outlives the instance. The
+            /// price is that those states are computed again for every signature.
+            ///
+            /// [`Zeroizing`]: zeroize::Zeroizing
+            #[cfg(feature = "zeroize")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+            pub fn new_zeroizing(
+                key: &zeroize::Zeroizing<Vec<u8>>,
+            ) -> Result<Self, ConstructError> {
+                Ok(Self {
+                    inner: HmacKey::new_zeroizing(key)?,
+                })
+            }
+
+            /// Generates a suitable key for this algorithm.
+            #[cfg(feature = "rand")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+            pub fn generate_key<R>(rng: &mut R) -> Vec<u8>
+            where
+                R: rand_core::CryptoRngCore,
+            {
+                use hmac::digest::crypto_common::KeySizeUser;
+                let mut vec = vec![0u8; Hmac::<$hash_ty>::key_size()];
+                rng.fill_bytes(&mut vec);
+                vec
+            }
+        }
+
+        impl RecommendHeaderParams for $struct_ident {
+            fn alg(&self) -> Algorithm {
+                Algorithm::Signing($algorithm)
+            }
+        }
+        impl JwsSigner for $struct_ident {
+            fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+                let mut inner = self.inner.mac();
+                inner.update(data);
+                let result = inner.finalize();
+                out.extend_from_slice(result.into_bytes().as_ref());
+            }
+            fn signature_len(&self) -> Option<usize> {
+                Some(<$hash_ty as hmac::digest::Digest>::output_size())
+            }
+        }
+
+        impl ValidateHeaderParams for $struct_ident {
+            fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+                alg == $algorithm
+            }
+        }
+        impl JwsVerifier for $struct_ident {
+            fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+                let mut inner = self.inner.mac();
+                inner.update(data);
+                // `verify_slice` compares in constant time and rejects signatures of any other
+                // length than the full MAC, so truncated (or extended) MACs never verify.
+                // Don't replace this with `verify_truncated_left`.
+                return inner.verify_slice(signature).is_ok();
+            }
+        }
+    };
+}
+
+pub(crate) use impl_hs;
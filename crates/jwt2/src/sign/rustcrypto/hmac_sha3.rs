@@ -0,0 +1,145 @@
+//! # Experimental HMAC/SHA3-based algorithms ([`HS3_256`], [`HS3_384`], [`HS3_512`])
+//!
+//! **These algorithms are not standardised.** RFC 7518 only defines HMAC with SHA2,
+//! and there is no registered `alg` value for HMAC with SHA3. `jwt2` uses `HS3-256`, `HS3-384`,
+//! and `HS3-512`, which some deployments use, but other libraries will most likely reject these
+//! tokens, or may use different names for the same thing. Only use them when you control
+//! both ends.
+//!
+//! ## Security considerations
+//! The same considerations as for the HMAC/SHA2-based algorithms apply. The key should be longer
+//! than the output of the underlying hash, as
+//! [section 3.2 of RFC 7518](https://www.rfc-editor.org/rfc/rfc7518.html#section-3.2) requires
+//! for those, but `jwt2` doesn't enforce this.
+//!
+//! `new` only borrows the key, but the hash states derived from it are just as good for forging
+//! MACs, and they aren't zeroized when dropped. With the `zeroize` feature, `new_zeroizing` keeps
+//! the key in a `zeroize::Zeroizing` container instead and derives the hash states anew for
+//! every MAC.
+
+use crate::header::{Algorithm, RecommendHeaderParams, ValidateHeaderParams};
+use crate::sign::hmac_common::{decode_hex, impl_hs, HmacKey};
+use crate::sign::{JwsSigner, JwsVerifier, SigningAlgorithm};
+use hmac::{Hmac, Mac};
+use sha3::{Sha3_256, Sha3_384, Sha3_512};
+
+pub use crate::sign::hmac_common::{ConstructError, FromHexError};
+
+/// HMAC using SHA3-256. Not interoperable; see the [module documentation](self).
+#[derive(Clone)]
+pub struct HS3_256 {
+    inner: HmacKey<Hmac<Sha3_256>>,
+}
+impl HS3_256 {
+    /// Creates a HS3-256 instance.
+    pub fn new(key: &[u8]) -> Result<Self, ConstructError> {
+        Ok(Self {
+            inner: HmacKey::new(key)?,
+        })
+    }
+}
+
+/// HMAC using SHA3-384. Not interoperable; see the [module documentation](self).
+#[derive(Clone)]
+pub struct HS3_384 {
+    inner: HmacKey<Hmac<Sha3_384>>,
+}
+impl HS3_384 {
+    /// Creates a HS3-384 instance.
+    pub fn new(key: &[u8]) -> Result<Self, ConstructError> {
+        Ok(Self {
+            inner: HmacKey::new(key)?,
+        })
+    }
+}
+
+/// HMAC using SHA3-512. Not interoperable; see the [module documentation](self).
+#[derive(Clone)]
+pub struct HS3_512 {
+    inner: HmacKey<Hmac<Sha3_512>>,
+}
+impl HS3_512 {
+    /// Creates a HS3-512 instance.
+    pub fn new(key: &[u8]) -> Result<Self, ConstructError> {
+        Ok(Self {
+            inner: HmacKey::new(key)?,
+        })
+    }
+}
+
+impl_hs!(
+    HS3_256:
+    alg = SigningAlgorithm::HS3_256,
+    hash = Sha3_256
+);
+impl_hs!(
+    HS3_384:
+    alg = SigningAlgorithm::HS3_384,
+    hash = Sha3_384
+);
+impl_hs!(
+    HS3_512:
+    alg = SigningAlgorithm::HS3_512,
+    hash = Sha3_512
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jwt::{Jwt, RawJwt};
+    use crate::{repr, Header};
+
+    #[test]
+    fn known_signatures() {
+        // Computed with Python's `hmac` and `hashlib` modules.
+        let cases: [(&dyn JwsVerifier, &str); 3] = [
+            (
+                &HS3_256::new(b"your-256-bit-secret").unwrap(),
+                "X-ds1oP0gIMnZIFNyyh_ySCIGuL0MRgnQVxNwtKrnM4",
+            ),
+            (
+                &HS3_384::new(b"your-384-bit-secret").unwrap(),
+                "1bbESRLK5rwks7LVWSEj4oEv5-sRzm6VOcKSob9xd_7-sBsGTr0--vQ1WdO3AJrp",
+            ),
+            (
+                &HS3_512::new(b"your-512-bit-secret").unwrap(),
+                "vpqGB4ojHNlNjVGqYLxUOvOGBhLRGz_wmAt7Etz55e_tR5sJwnUvLx0UQvY6I74_4ln5ZoTynp3O1E1FZc6sPA",
+            ),
+        ];
+        for (verifier, signature) in cases {
+            let signature = repr::decode_bytes_from_base64url(signature).unwrap();
+            assert!(verifier.verify_signature(b"hello", &signature));
+            assert!(!verifier.verify_signature(b"hallo", &signature));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let hs3_256 = HS3_256::new(b"your-256-bit-secret").unwrap();
+        let header = Header::recommended(&hs3_256);
+        assert_eq!(
+            serde_json::to_value(&header).unwrap()["alg"],
+            serde_json::json!("HS3-256")
+        );
+
+        let token = Jwt::builder()
+            .header(header)
+            .claim("sub", "1234567890")
+            .sign(&hs3_256)
+            .expect("Could not sign");
+        let jwt = RawJwt::decode(&token).expect("Could not decode");
+        assert_eq!(
+            jwt.header.algorithm,
+            Algorithm::Signing(SigningAlgorithm::HS3_256)
+        );
+        assert!(jwt.verify_signature(&hs3_256));
+        assert!(!jwt.verify_signature(&HS3_384::new(b"your-256-bit-secret").unwrap()));
+        assert!(!jwt.verify_signature(&HS3_256::new(b"another-256-bit-secret").unwrap()));
+
+        for alg in ["HS3-256", "HS3-384", "HS3-512"] {
+            let parsed: SigningAlgorithm = alg.parse().unwrap();
+            assert!(parsed.is_hmac());
+            assert_eq!(parsed.to_string(), alg);
+        }
+    }
+}
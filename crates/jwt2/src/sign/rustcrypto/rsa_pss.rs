@@ -0,0 +1,414 @@
+//! # RSA-based algorithms using PSS ([`PS256`], [`PS384`], [`PS512`])
+//!
+//! As required by [section 3.5 of RFC 7518](https://www.rfc-editor.org/rfc/rfc7518.html#section-3.5),
+//! the salt is as long as the hash output by default, and MGF1 uses the same hash as the
+//! signature. PSS signatures are randomised, so signing the same data twice gives different
+//! signatures, unless the salt is empty; see [`SaltLength`].
+//!
+//! To verify both `RS*` and `PS*` tokens with a single key, see
+//! [`RsaPublic`](crate::sign::rsa_pkcs1::RsaPublic).
+
+use crate::sign::rsa_pkcs1::RSAVerifierConfig;
+use crate::{
+    Algorithm, JwsSigner, JwsVerifier, RecommendHeaderParams, SigningAlgorithm,
+    ValidateHeaderParams,
+};
+use base64ct::LineEnding;
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rsa::pss::{Signature, SigningKey, VerifyingKey};
+use rsa::traits::PublicKeyParts;
+use sha2::digest::DynDigest;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use signature::{Keypair, SignatureEncoding};
+
+macro_rules! impl_ps {
+    (
+        $(#[$main_attrs:meta])*
+        main: $main_ident:ident,
+        $(#[$public_attrs:meta])*
+        public: $public_ident:ident,
+        hash: $hash_ty:ty
+    ) => {
+        $(#[$main_attrs])*
+        pub type $main_ident = GenericRsaPssImpl<SigningKey<$hash_ty>>;
+        $(#[$public_attrs])*
+        pub type $public_ident = GenericRsaPssImpl<VerifyingKey<$hash_ty>>;
+
+        impl $main_ident {
+            /// Create an instance of this algorithm from a signing key.
+            pub fn from(key: SigningKey<$hash_ty>) -> Self {
+                Self {
+                    key,
+                    config: RSAVerifierConfig::default(),
+                }
+            }
+
+            pub fn parse_pkcs1_pem(key: &str) -> rsa::pkcs1::Result<Self> {
+                rsa::RsaPrivateKey::from_pkcs1_pem(key).map(|key| Self::from(SigningKey::new(key)))
+            }
+            pub fn parse_pkcs8_pem(key: &str) -> rsa::pkcs8::Result<Self> {
+                rsa::RsaPrivateKey::from_pkcs8_pem(key).map(|key| Self::from(SigningKey::new(key)))
+            }
+
+            pub fn encode_as_pkcs8_pem(&self) -> rsa::pkcs8::Result<rsa::pkcs8::der::zeroize::Zeroizing<String>> {
+                let key: &rsa::RsaPrivateKey = self.key.as_ref();
+                EncodePrivateKey::to_pkcs8_pem(key, LineEnding::default())
+            }
+
+            /// Generates a new key.
+            #[cfg(feature = "rand")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+            pub fn new_rand<R>(rng: &mut R, bits: usize) -> rsa::Result<Self> where R: rand_core::CryptoRngCore {
+                Ok(Self::from(SigningKey::random(rng, bits)?))
+            }
+
+            /// Generates a new key of `bits` bits using the operating system's RNG.
+            ///
+            /// See [`Self::new_rand`] to use a different RNG.
+            #[cfg(feature = "rand")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+            pub fn generate(bits: usize) -> rsa::Result<Self> {
+                Self::new_rand(&mut rand_core::OsRng, bits)
+            }
+
+            /// Returns a signer using a salt of `salt_length` instead of the default
+            /// [`SaltLength::DigestLength`].
+            pub fn with_salt_length(self, salt_length: SaltLength) -> Self {
+                let key: &rsa::RsaPrivateKey = self.key.as_ref();
+                let salt_len = salt_length.resolve(key, <$hash_ty as Digest>::output_size());
+                Self::from(SigningKey::new_with_salt_len(key.clone(), salt_len))
+                    .with_config(self.config.clone())
+            }
+
+            /// Gets the public half of this key.
+            ///
+            /// Note that [`GenericRsaPssImpl::get_key`] gives access to the private key as well.
+            pub fn verifying_key(&self) -> &rsa::RsaPublicKey {
+                let key: &rsa::RsaPrivateKey = self.key.as_ref();
+                key.as_ref()
+            }
+            /// Encodes the public key as PKCS#1 DER (i.e. `RSAPublicKey`).
+            pub fn public_key_bytes(&self) -> rsa::pkcs1::Result<Vec<u8>> {
+                crate::sign::rsa_pkcs1::public_key_bytes(self.verifying_key())
+            }
+
+            /// Creates a corresponding verifying-only instance from `self`, with the same
+            /// [config](GenericRsaPssImpl::get_config).
+            pub fn public(&self) -> $public_ident {
+                $public_ident::from(self.key.verifying_key()).with_config(self.config.clone())
+            }
+        }
+        impl $public_ident {
+            /// Create an instance of this algorithm from a verifying key.
+            pub fn from(key: VerifyingKey<$hash_ty>) -> Self {
+                Self {
+                    key,
+                    config: RSAVerifierConfig::default(),
+                }
+            }
+            pub fn parse_pkcs1_pem(key: &str) -> rsa::pkcs1::Result<Self> {
+                rsa::RsaPublicKey::from_pkcs1_pem(key).map(|key| Self::from(VerifyingKey::new(key)))
+            }
+            pub fn parse_pkcs8_pem(key: &str) -> rsa::pkcs8::spki::Result<Self> {
+                rsa::RsaPublicKey::from_public_key_pem(key).map(|key| Self::from(VerifyingKey::new(key)))
+            }
+
+            pub fn encode_as_pkcs8_pem(&self) -> rsa::pkcs8::spki::Result<String> {
+                EncodePublicKey::to_public_key_pem(self.verifying_key(), LineEnding::default())
+            }
+
+            pub fn verifying_key(&self) -> &rsa::RsaPublicKey {
+                self.key.as_ref()
+            }
+            /// Encodes the public key as PKCS#1 DER (i.e. `RSAPublicKey`).
+            pub fn public_key_bytes(&self) -> rsa::pkcs1::Result<Vec<u8>> {
+                crate::sign::rsa_pkcs1::public_key_bytes(self.verifying_key())
+            }
+        }
+        impl From<rsa::RsaPublicKey> for $public_ident {
+            fn from(key: rsa::RsaPublicKey) -> Self {
+                Self::from(VerifyingKey::new(key))
+            }
+        }
+        impl JwsVerifier for $public_ident {
+            fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+                let key: &rsa::RsaPublicKey = self.key.as_ref();
+                if !self.config.allows(key) {
+                    return false;
+                }
+                verify_pss::<$hash_ty>(&self.config, key, data, signature)
+            }
+        }
+        impl Algo for $main_ident {
+            const ALGORITHM: SigningAlgorithm = SigningAlgorithm::$main_ident;
+        }
+        impl Algo for $public_ident {
+            const ALGORITHM: SigningAlgorithm = SigningAlgorithm::$main_ident;
+        }
+    };
+}
+
+impl_ps!(
+    /// RSASSA-PSS using SHA2-256 and MGF1 with SHA2-256.
+    ///
+    /// If you only need verifying capabilities, try [`PS256Public`].
+    main: PS256,
+    public: PS256Public,
+    hash: Sha256
+);
+impl_ps!(
+    /// RSASSA-PSS using SHA2-384 and MGF1 with SHA2-384.
+    ///
+    /// If you only need verifying capabilities, try [`PS384Public`].
+    main: PS384,
+    public: PS384Public,
+    hash: Sha384
+);
+impl_ps!(
+    /// RSASSA-PSS using SHA2-512 and MGF1 with SHA2-512.
+    ///
+    /// If you only need verifying capabilities, try [`PS512Public`].
+    main: PS512,
+    public: PS512Public,
+    hash: Sha512
+);
+
+/// The length of the salt used when creating PSS signatures.
+///
+/// The salt length isn't included in the signature, so verifiers have to guess it.
+/// `jwt2`'s verifiers only accept the [digest length](SaltLength::DigestLength) by default.
+/// With [`RSAVerifierConfig::lenient_pss_salt`], they also accept the
+/// [maximum](SaltLength::Maximum) and an empty salt; signatures using any other length
+/// never verify with them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SaltLength {
+    /// As long as the hash output, as required by RFC 7518.
+    #[default]
+    DigestLength,
+    /// As long as the key allows.
+    Maximum,
+    /// Exactly this many bytes. An empty salt (`Exact(0)`) makes signatures deterministic.
+    Exact(usize),
+}
+impl SaltLength {
+    /// The salt lengths accepted when verifying with
+    /// [`lenient_pss_salt`](RSAVerifierConfig::lenient_pss_salt).
+    const LENIENT: [Self; 3] = [Self::DigestLength, Self::Maximum, Self::Exact(0)];
+
+    fn resolve(self, key: &impl PublicKeyParts, digest_len: usize) -> usize {
+        match self {
+            Self::DigestLength => digest_len,
+            Self::Maximum => {
+                // See step 3 of section 9.1.1 of RFC 8017.
+                let em_len = (key.n().bits() - 1).div_ceil(8);
+                em_len.saturating_sub(digest_len + 2)
+            }
+            Self::Exact(len) => len,
+        }
+    }
+}
+
+/// Verifies a PSS signature made with any of the [salt lengths](SaltLength) `config` accepts.
+///
+/// This doesn't check the key size; callers check [`RSAVerifierConfig::min_bits`] first.
+pub(crate) fn verify_pss<D>(
+    config: &RSAVerifierConfig,
+    key: &rsa::RsaPublicKey,
+    data: &[u8],
+    signature: &[u8],
+) -> bool
+where
+    D: 'static + Digest + DynDigest + Send + Sync,
+{
+    let salt_lengths: &[SaltLength] = if config.lenient_pss_salt {
+        &SaltLength::LENIENT
+    } else {
+        &[SaltLength::DigestLength]
+    };
+    let hashed = D::digest(data);
+    let digest_len = <D as Digest>::output_size();
+    salt_lengths.iter().any(|salt_length| {
+        let scheme = rsa::Pss::new_with_salt::<D>(salt_length.resolve(key, digest_len));
+        key.verify(scheme, &hashed, signature).is_ok()
+    })
+}
+
+/// RSA-PSS algorithm.
+#[derive(Clone)]
+pub struct GenericRsaPssImpl<Key> {
+    key: Key,
+    config: RSAVerifierConfig,
+}
+impl<Key> GenericRsaPssImpl<Key> {
+    pub fn get_key(&self) -> &Key {
+        &self.key
+    }
+    /// Gets the policy used when verifying signatures.
+    pub fn get_config(&self) -> &RSAVerifierConfig {
+        &self.config
+    }
+    /// Replaces the policy used when verifying signatures.
+    ///
+    /// For signers, this is the policy given to the verifier created by `public()`.
+    pub fn with_config(mut self, config: RSAVerifierConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+trait Algo {
+    const ALGORITHM: SigningAlgorithm;
+}
+
+impl<Key> RecommendHeaderParams for GenericRsaPssImpl<Key>
+where
+    Self: Algo,
+{
+    fn alg(&self) -> Algorithm {
+        Algorithm::Signing(Self::ALGORITHM)
+    }
+}
+impl<Key> JwsSigner for GenericRsaPssImpl<Key>
+where
+    Key: signature::Signer<Signature> + AsRef<rsa::RsaPrivateKey>,
+    Self: Algo,
+{
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.key.sign(data).to_bytes());
+    }
+    fn signature_len(&self) -> Option<usize> {
+        Some(self.key.as_ref().size())
+    }
+}
+
+impl<Key> ValidateHeaderParams for GenericRsaPssImpl<Key>
+where
+    Self: Algo,
+{
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        alg == Self::ALGORITHM
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> rsa::RsaPrivateKey {
+        // Generating a key is slow in debug builds, so keep it small.
+        rsa::RsaPrivateKey::new(&mut rand_core::OsRng, 1024).expect("Could not generate key")
+    }
+    /// Accepts the keys from [`key`], which the default config rejects.
+    fn small_keys() -> RSAVerifierConfig {
+        RSAVerifierConfig {
+            min_bits: 1024,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn salt_lengths() {
+        let ps256 = PS256::from(SigningKey::new(key())).with_config(small_keys());
+        let strict = ps256.public();
+        let lenient = strict.clone().with_config(RSAVerifierConfig {
+            lenient_pss_salt: true,
+            ..small_keys()
+        });
+        let rsa_public = crate::sign::RsaPublic::new(strict.verifying_key().clone());
+        let strict_rsa = rsa_public.clone().with_config(small_keys());
+        let lenient_rsa = rsa_public.with_config(lenient.get_config().clone());
+
+        for salt_length in SaltLength::LENIENT {
+            let signer = ps256.clone().with_salt_length(salt_length);
+            let signature = signer.sign(b"hello");
+            assert!(
+                lenient.verify_signature(b"hello", &signature),
+                "{salt_length:?}"
+            );
+            assert!(
+                !lenient.verify_signature(b"hallo", &signature),
+                "{salt_length:?}"
+            );
+            assert!(lenient_rsa.verify_with_algorithm(
+                SigningAlgorithm::PS256,
+                b"hello",
+                &signature
+            ));
+            assert!(!lenient_rsa.verify_with_algorithm(
+                SigningAlgorithm::PS384,
+                b"hello",
+                &signature
+            ));
+
+            // Only the digest length is accepted by default.
+            let accepted = salt_length == SaltLength::DigestLength;
+            assert_eq!(
+                strict.verify_signature(b"hello", &signature),
+                accepted,
+                "{salt_length:?}"
+            );
+            assert_eq!(
+                strict_rsa.verify_with_algorithm(SigningAlgorithm::PS256, b"hello", &signature),
+                accepted,
+                "{salt_length:?}"
+            );
+        }
+
+        // An empty salt is deterministic, the default isn't.
+        let deterministic = ps256.clone().with_salt_length(SaltLength::Exact(0));
+        assert_eq!(deterministic.sign(b"hello"), deterministic.sign(b"hello"));
+        assert_ne!(ps256.sign(b"hello"), ps256.sign(b"hello"));
+
+        // Other lengths can't be guessed.
+        let unusual = ps256.with_salt_length(SaltLength::Exact(7));
+        assert!(!lenient.verify_signature(b"hello", &unusual.sign(b"hello")));
+    }
+
+    #[test]
+    fn public_key_accessors() {
+        let ps384 = PS384::from(SigningKey::new(key()));
+        let public = ps384.public();
+        assert_eq!(ps384.verifying_key(), public.verifying_key());
+        assert_eq!(
+            ps384.public_key_bytes().unwrap(),
+            public.public_key_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn maximum_salt_length() {
+        let ps512 = PS512::from(SigningKey::new(key()))
+            .with_config(RSAVerifierConfig {
+                lenient_pss_salt: true,
+                ..small_keys()
+            })
+            .with_salt_length(SaltLength::Maximum);
+        assert!(ps512
+            .public()
+            .verify_signature(b"hello", &ps512.sign(b"hello")));
+    }
+
+    #[test]
+    fn min_bits() {
+        let ps256 = PS256::from(SigningKey::new(key()));
+        let signature = ps256.sign(b"hello");
+        let key = ps256.verifying_key().clone();
+
+        assert_eq!(ps256.get_config(), &RSAVerifierConfig::default());
+        assert!(!ps256.public().verify_signature(b"hello", &signature));
+        let public = <PS256Public as From<_>>::from(key.clone());
+        assert!(!public.verify_signature(b"hello", &signature));
+        let rsa_public = crate::sign::RsaPublic::from(key.clone());
+        assert!(!rsa_public.verify_with_algorithm(SigningAlgorithm::PS256, b"hello", &signature));
+
+        // The same signature is fine once small keys are allowed.
+        let public = ps256.with_config(small_keys()).public();
+        assert_eq!(public.get_config(), &small_keys());
+        assert!(public.verify_signature(b"hello", &signature));
+        let public = <PS256Public as From<_>>::from(key).with_config(small_keys());
+        assert!(public.verify_signature(b"hello", &signature));
+        let rsa_public = rsa_public.with_config(small_keys());
+        assert!(rsa_public.verify_with_algorithm(SigningAlgorithm::PS256, b"hello", &signature));
+    }
+}
@@ -0,0 +1,232 @@
+//! Utilities for testing code that verifies JWTs.
+//!
+//! [`TokenBuilder`] creates tokens in the shapes attackers tend to send — a swapped `alg`,
+//! a missing signature, a tampered payload, or a header with duplicate parameters — so you can
+//! make sure your verification code rejects them.
+//!
+//! This is only meant for tests; don't enable the `testing` feature outside of
+//! `[dev-dependencies]`.
+
+use crate::sign::SignError;
+use crate::{repr, Header, JwsSigner};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Builds possibly malformed tokens for negative tests.
+///
+/// Without any of the modifiers, this creates a validly signed token, although the header
+/// parameters may be in a different order than [`JwtData::sign_with`](crate::JwtData::sign_with)
+/// would put them in.
+///
+/// ```
+/// # #[cfg(feature = "hmac-sha2")] {
+/// use jwt2::jwt::RawJwt;
+/// use jwt2::sign::HS256;
+/// use jwt2::testing::TokenBuilder;
+/// use jwt2::Header;
+/// use serde_json::json;
+///
+/// let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+/// let claims = json!({ "sub": "1234567890", "admin": false });
+/// let builder = TokenBuilder::new(&Header::recommended(&hs256), &claims);
+///
+/// // The untouched token verifies...
+/// let token = builder.clone().build(&hs256).unwrap();
+/// assert!(RawJwt::decode(&token).unwrap().verify_signature(&hs256));
+///
+/// // ...but none of the malformed ones do.
+/// let tampered = builder.clone().tamper_payload(&json!({ "sub": "1234567890", "admin": true }));
+/// let unsigned = builder.clone().algorithm("none").empty_signature();
+/// let swapped = builder.clone().algorithm("HS384");
+/// for malformed in [tampered, unsigned, swapped] {
+///     let token = malformed.build(&hs256).unwrap();
+///     assert!(!RawJwt::decode(&token).is_ok_and(|jwt| jwt.verify_signature(&hs256)));
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenBuilder {
+    header: Map<String, Value>,
+    duplicate_params: Vec<(String, Value)>,
+    claims: Value,
+    tampered_claims: Option<Value>,
+    signature: SignatureMode,
+}
+
+#[derive(Debug, Clone)]
+enum SignatureMode {
+    Signed,
+    Empty,
+    Raw(Vec<u8>),
+}
+
+impl TokenBuilder {
+    /// Creates a builder for a token with `header` and `claims`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `claims` can't be serialised to JSON.
+    pub fn new<Claims>(header: &Header, claims: &Claims) -> Self
+    where
+        Claims: ?Sized + Serialize,
+    {
+        let header = match serde_json::to_value(header) {
+            Ok(Value::Object(header)) => header,
+            _ => unreachable!("headers always serialise to JSON objects"),
+        };
+        Self {
+            header,
+            duplicate_params: Vec::new(),
+            claims: serde_json::to_value(claims).expect("Could not serialise claims"),
+            tampered_claims: None,
+            signature: SignatureMode::Signed,
+        }
+    }
+
+    /// Sets a header parameter to an arbitrary value.
+    ///
+    /// The modified header is what gets signed.
+    pub fn header_param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.header.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replaces the `alg` header parameter, e.g. with `none` or an algorithm other than the
+    /// signer's.
+    ///
+    /// The token is still signed by the signer passed to [`TokenBuilder::build`].
+    pub fn algorithm(self, alg: &str) -> Self {
+        self.header_param("alg", alg)
+    }
+
+    /// Appends a parameter to the header even if it's already present, creating a header with
+    /// duplicate keys.
+    ///
+    /// JSON parsers disagree on which duplicate wins, which is a classic source of confusion
+    /// between components.
+    pub fn duplicate_header_param(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<Value>,
+    ) -> Self {
+        self.duplicate_params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Replaces the payload with `claims` *after* signing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `claims` can't be serialised to JSON.
+    pub fn tamper_payload<Claims>(mut self, claims: &Claims) -> Self
+    where
+        Claims: ?Sized + Serialize,
+    {
+        self.tampered_claims =
+            Some(serde_json::to_value(claims).expect("Could not serialise claims"));
+        self
+    }
+
+    /// Leaves the signature empty, e.g. `<header>.<payload>.`.
+    pub fn empty_signature(mut self) -> Self {
+        self.signature = SignatureMode::Empty;
+        self
+    }
+
+    /// Uses `signature` instead of actually signing the token.
+    pub fn raw_signature(mut self, signature: impl Into<Vec<u8>>) -> Self {
+        self.signature = SignatureMode::Raw(signature.into());
+        self
+    }
+
+    /// Creates the token, signing it with `signer` unless the signature was overridden.
+    ///
+    /// Fails if `signer` refuses to sign.
+    pub fn build<Signer>(self, signer: &Signer) -> Result<String, SignError>
+    where
+        Signer: ?Sized + JwsSigner,
+    {
+        let mut header = Value::Object(self.header).to_string();
+        for (name, value) in self.duplicate_params {
+            // The header is always a non-empty object since `alg` is required.
+            header.pop();
+            header.push_str(&format!(",{}:{}}}", Value::from(name), value));
+        }
+
+        let header = repr::encode_bytes_as_base64url(header.as_bytes());
+        let payload = repr::encode_bytes_as_base64url(self.claims.to_string().as_bytes());
+        let signing_input = format!("{header}.{payload}");
+
+        let signature = match self.signature {
+            SignatureMode::Signed => signer.try_sign(signing_input.as_bytes())?,
+            SignatureMode::Empty => Vec::new(),
+            SignatureMode::Raw(signature) => signature,
+        };
+        let payload = match self.tampered_claims {
+            Some(claims) => repr::encode_bytes_as_base64url(claims.to_string().as_bytes()),
+            None => payload,
+        };
+
+        Ok(format!(
+            "{header}.{payload}.{}",
+            repr::encode_bytes_as_base64url(&signature)
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "hmac-sha2"))]
+mod tests {
+    use super::*;
+    use crate::jwt::RawJwt;
+    use crate::sign::HS256;
+    use serde_json::json;
+
+    fn builder(hs256: &HS256) -> TokenBuilder {
+        TokenBuilder::new(&Header::recommended(hs256), &json!({ "sub": "1234567890" }))
+    }
+
+    #[test]
+    fn malformed_tokens_are_rejected() {
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let verify = |token: Result<String, SignError>| {
+            RawJwt::decode(&token.unwrap()).is_ok_and(|jwt| jwt.verify_signature(&hs256))
+        };
+
+        assert!(verify(builder(&hs256).build(&hs256)));
+        assert!(!verify(builder(&hs256).algorithm("none").build(&hs256)));
+        assert!(!verify(builder(&hs256).empty_signature().build(&hs256)));
+        assert!(!verify(
+            builder(&hs256).raw_signature([0; 32]).build(&hs256)
+        ));
+        assert!(!verify(
+            builder(&hs256)
+                .tamper_payload(&json!({ "sub": "0" }))
+                .build(&hs256)
+        ));
+    }
+
+    #[test]
+    fn duplicate_header_param() {
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let token = builder(&hs256)
+            .duplicate_header_param("alg", "none")
+            .build(&hs256)
+            .unwrap();
+        let header = token.split('.').next().unwrap();
+        let header = String::from_utf8(repr::decode_bytes_from_base64url(header).unwrap()).unwrap();
+        assert_eq!(header.matches("\"alg\"").count(), 2);
+        assert!(header.ends_with(r#","alg":"none"}"#));
+    }
+
+    #[test]
+    fn refused_signature() {
+        let hs256 = HS256::new(b"your-256-bit-secret").unwrap();
+        let exhausted = crate::util::Counted::with_limit(hs256.clone(), 0);
+        assert!(matches!(
+            builder(&hs256).build(&exhausted),
+            Err(SignError::LimitReached { limit: 0 })
+        ));
+        // Nothing is signed when the signature is overridden.
+        assert!(builder(&hs256).empty_signature().build(&exhausted).is_ok());
+    }
+}
@@ -1,15 +1,31 @@
 mod with_keyid;
+mod with_crit_handler;
+mod with_header_defaults;
+mod counted;
 mod trait_impls;
+mod algorithm_registry;
+mod timed;
+mod verifier_registry;
 
 pub use with_keyid::*;
+pub use with_crit_handler::*;
+pub use with_header_defaults::*;
+pub use counted::*;
+pub use algorithm_registry::*;
+pub use timed::*;
+pub use verifier_registry::*;
 
 macro_rules! algorithms_decl {
+    // Variants are named after themselves unless they say otherwise.
+    (@name $variant_ident:ident) => { stringify!($variant_ident) };
+    (@name $variant_ident:ident $variant_name:literal) => { $variant_name };
     (
         $(#[$enum_attrs:meta])*
         $enum_ident:ident;
         $(
             $(#[$variant_attrs:meta])* $variant_ident:ident {
                 $(cfg: $(#[$variant_attrs_cfg:meta])*;)?
+                $(name: $variant_name:literal;)?
             }
         ),*
     ) => {
@@ -22,13 +38,25 @@ macro_rules! algorithms_decl {
             )*
         }
 
-        #[allow(unreachable_code, unreachable_patterns)]
+        // The names are consts so that they can be used as patterns, which is why they're named
+        // after the variants even when that isn't upper case (e.g. `EdDSA`).
+        #[allow(unreachable_code, unreachable_patterns, unused_variables, non_upper_case_globals)]
         const _: () = {
             $(
             $( $(#[$variant_attrs_cfg])* )?
-            const $variant_ident: &'static str = stringify!($variant_ident);
+            const $variant_ident: &'static str = algorithms_decl!(@name $variant_ident $($variant_name)?);
             )*
 
+            impl $enum_ident {
+                /// Every variant enabled by the current features, in declaration order.
+                pub const ALL: &'static [Self] = &[
+                    $(
+                    $( $(#[$variant_attrs_cfg])* )?
+                    Self::$variant_ident,
+                    )*
+                ];
+            }
+
             impl core::str::FromStr for $enum_ident {
                 type Err = ();
 
@@ -36,24 +64,37 @@ macro_rules! algorithms_decl {
                 fn from_str(value: &str) -> Result<Self, Self::Err> {
                     // I prefer to be liberal in what is accepted, but RFC 7515 specifies that
                     // algorithm names are indeed case-sensitive.
-                    $(
+                    match value {
+                        $(
                         $( $(#[$variant_attrs_cfg])* )?
-                        if value.eq($variant_ident) {
-                            return Ok(Self::$variant_ident);
-                        }
-                    )*
-                    Err(())
+                        $variant_ident => Ok(Self::$variant_ident),
+                        )*
+                        _ => Err(()),
+                    }
+                }
+            }
+
+            impl TryFrom<&str> for $enum_ident {
+                type Error = crate::sign::UnknownAlgorithmError;
+
+                /// Tries to get an algorithm from a string, like [`FromStr`](core::str::FromStr)
+                /// but with an error that says which name wasn't recognised.
+                fn try_from(value: &str) -> Result<Self, Self::Error> {
+                    value.parse().map_err(|()| crate::sign::UnknownAlgorithmError {
+                        name: value.to_string(),
+                    })
                 }
             }
 
             impl core::fmt::Display for $enum_ident {
                 fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                    match self {
+                    // No wildcard arm, so that this is still exhaustive (and can't go wrong)
+                    // when every variant is disabled.
+                    match *self {
                         $(
                         $( $(#[$variant_attrs_cfg])* )?
                         Self::$variant_ident => f.write_str($variant_ident),
                         )*
-                        _ => f.write_str("<invalid>")
                     }
                 }
             }
@@ -62,12 +103,11 @@ macro_rules! algorithms_decl {
                 fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
                     where S: serde::ser::Serializer
                 {
-                    match self {
+                    match *self {
                         $(
                         $( $(#[$variant_attrs_cfg])* )?
                         Self::$variant_ident => ser.serialize_str($variant_ident),
                         )*
-                        _ => panic!("The default match should not be reachable for this enum")
                     }
                 }
             }
@@ -84,12 +124,15 @@ macro_rules! algorithms_decl {
                         }
 
                         fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: serde::de::Error {
-                            $(
-                            $( $(#[$variant_attrs_cfg])* )?
-                            if value.eq($variant_ident) {
-                                return Ok($enum_ident::$variant_ident);
+                            // Matching on the names (rather than comparing them one by one) lets
+                            // the compiler dispatch on the length first.
+                            match value {
+                                $(
+                                $( $(#[$variant_attrs_cfg])* )?
+                                $variant_ident => return Ok($enum_ident::$variant_ident),
+                                )*
+                                _ => {}
                             }
-                            )*
 
                             const VARIANTS: &[&'static str] = &[
                                 $(
@@ -109,7 +152,3 @@ macro_rules! algorithms_decl {
 }
 
 pub(crate) use algorithms_decl;
-
-pub(crate) fn to_byte_vec(r: &[u8]) -> Vec<u8> {
-    Vec::from(r)
-}
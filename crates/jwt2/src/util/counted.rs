@@ -0,0 +1,136 @@
+use crate::sign::SignError;
+use crate::{Algorithm, JwsSigner, RecommendHeaderParams};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counts the signatures created by a signer, optionally refusing to sign after a maximum.
+///
+/// This is meant for keys with usage policies, e.g. a hardware-backed key that may only
+/// create a certain number of signatures. Only successful signatures are counted.
+///
+/// Since [`JwsSigner::sign_to`] can't fail, it panics once the limit has been reached;
+/// use [`JwsSigner::try_sign_to`] (which all of `jwt2`'s token creation does) to get a
+/// [`SignError::LimitReached`] instead.
+///
+/// See [`Counted::new`] and [`Counted::with_limit`].
+pub struct Counted<Inner> {
+    pub inner: Inner,
+    /// The maximum number of signatures, if any.
+    pub limit: Option<u64>,
+    count: AtomicU64,
+}
+impl<Inner> Counted<Inner> {
+    /// Creates a new [`Counted`] that only counts, without a limit.
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            limit: None,
+            count: AtomicU64::new(0),
+        }
+    }
+    /// Creates a new [`Counted`] that refuses to create more than `limit` signatures.
+    pub fn with_limit(inner: Inner, limit: u64) -> Self {
+        Self {
+            inner,
+            limit: Some(limit),
+            count: AtomicU64::new(0),
+        }
+    }
+    /// The number of signatures created so far.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    fn reserve(&self) -> Result<(), SignError> {
+        let limit = self.limit.unwrap_or(u64::MAX);
+        self.count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                (count < limit).then_some(count + 1)
+            })
+            .map(|_| ())
+            .map_err(|_| SignError::LimitReached { limit })
+    }
+}
+
+impl<Inner> RecommendHeaderParams for Counted<Inner>
+where
+    Inner: RecommendHeaderParams,
+{
+    fn alg(&self) -> Algorithm {
+        self.inner.alg()
+    }
+    fn kid(&self) -> Option<&str> {
+        self.inner.kid()
+    }
+    fn typ(&self) -> Option<&str> {
+        self.inner.typ()
+    }
+    fn cty(&self) -> Option<&str> {
+        self.inner.cty()
+    }
+}
+
+impl<Inner> JwsSigner for Counted<Inner>
+where
+    Inner: JwsSigner,
+{
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        if let Err(e) = self.try_sign_to(data, out) {
+            panic!("{e}");
+        }
+    }
+    fn signature_len(&self) -> Option<usize> {
+        self.inner.signature_len()
+    }
+    fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+        self.reserve()?;
+        self.inner.try_sign_to(data, out).inspect_err(|_| {
+            // Refused signatures don't count.
+            self.count.fetch_sub(1, Ordering::SeqCst);
+        })
+    }
+}
+
+#[cfg(all(test, feature = "hmac-sha2"))]
+mod tests {
+    use super::*;
+    use crate::jwt::{JwtCreateError, JwtData};
+    use crate::sign::HS256;
+    use crate::Header;
+
+    #[test]
+    fn stops_after_limit() {
+        let signer = Counted::with_limit(HS256::new(b"your-256-bit-secret").unwrap(), 2);
+        let data = JwtData {
+            header: Header::recommended(&signer),
+            claims: serde_json::json!({}),
+        };
+
+        assert!(data.sign_with(&signer).is_ok());
+        assert!(signer.try_sign(b"hello").is_ok());
+        assert_eq!(signer.count(), 2);
+
+        assert!(matches!(
+            signer.try_sign(b"hello"),
+            Err(SignError::LimitReached { limit: 2 })
+        ));
+        assert!(matches!(
+            data.sign_with(&signer),
+            Err(JwtCreateError::Sign(SignError::LimitReached { limit: 2 }))
+        ));
+        assert_eq!(signer.count(), 2);
+
+        // The limit applies through other wrappers too.
+        let with_key_id = crate::util::WithKeyId::new("key".to_string(), &signer);
+        assert!(data.sign_with(&with_key_id).is_err());
+        assert!(matches!(
+            crate::sign::sign(&signer, b"hello"),
+            Err(SignError::LimitReached { limit: 2 })
+        ));
+    }
+
+    #[test]
+    #[should_panic = "limit of 0 signatures"]
+    fn infallible_sign_panics() {
+        Counted::with_limit(HS256::new(b"your-256-bit-secret").unwrap(), 0).sign(b"hello");
+    }
+}
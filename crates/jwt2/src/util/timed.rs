@@ -0,0 +1,142 @@
+use crate::sign::SignError;
+use crate::{
+    Algorithm, CritHandler, Header, JwsSigner, JwsVerifier, RecommendHeaderParams,
+    SigningAlgorithm, ValidateHeaderParams,
+};
+use std::time::{Duration, Instant};
+
+/// Measures how long signing and verifying take, e.g. to record latency metrics.
+///
+/// `callback` is called with the elapsed time after every signature that is created or
+/// verified, whether or not that succeeded. Checking the header isn't measured.
+///
+/// ```
+/// # #[cfg(feature = "hmac-sha2")] {
+/// use jwt2::sign::HS256;
+/// use jwt2::util::Timed;
+/// use jwt2::JwsSigner;
+///
+/// let signer = Timed::new(HS256::new(b"your-256-bit-secret").unwrap(), |elapsed| {
+///     println!("HS256 took {elapsed:?}");
+/// });
+/// signer.sign(b"hello");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Timed<Inner, Callback> {
+    pub inner: Inner,
+    pub callback: Callback,
+}
+impl<Inner, Callback> Timed<Inner, Callback>
+where
+    Callback: Fn(Duration),
+{
+    pub fn new(inner: Inner, callback: Callback) -> Self {
+        Self { inner, callback }
+    }
+
+    fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        (self.callback)(start.elapsed());
+        result
+    }
+}
+
+impl<Inner, Callback> RecommendHeaderParams for Timed<Inner, Callback>
+where
+    Inner: RecommendHeaderParams,
+{
+    fn alg(&self) -> Algorithm {
+        self.inner.alg()
+    }
+    fn kid(&self) -> Option<&str> {
+        self.inner.kid()
+    }
+    fn typ(&self) -> Option<&str> {
+        self.inner.typ()
+    }
+    fn cty(&self) -> Option<&str> {
+        self.inner.cty()
+    }
+}
+
+impl<Inner, Callback> JwsSigner for Timed<Inner, Callback>
+where
+    Inner: JwsSigner,
+    Callback: Fn(Duration),
+{
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        self.time(|| self.inner.sign_to(data, out))
+    }
+    fn signature_len(&self) -> Option<usize> {
+        self.inner.signature_len()
+    }
+    fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+        self.time(|| self.inner.try_sign_to(data, out))
+    }
+}
+
+impl<Inner, Callback> ValidateHeaderParams for Timed<Inner, Callback>
+where
+    Inner: ValidateHeaderParams,
+{
+    fn validate_header(&self, header: &Header) -> bool {
+        self.inner.validate_header(header)
+    }
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        self.inner.supports_algorithm(alg)
+    }
+    fn key_id(&self) -> Option<&str> {
+        self.inner.key_id()
+    }
+    fn crit_handler(&self) -> Option<&dyn CritHandler> {
+        self.inner.crit_handler()
+    }
+}
+
+impl<Inner, Callback> JwsVerifier for Timed<Inner, Callback>
+where
+    Inner: JwsVerifier,
+    Callback: Fn(Duration),
+{
+    fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+        self.time(|| self.inner.verify_signature(data, signature))
+    }
+    fn verify_signature_with_header(&self, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+        self.time(|| {
+            self.inner
+                .verify_signature_with_header(header, data, signature)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "hmac-sha2"))]
+mod tests {
+    use super::*;
+    use crate::jwt::{example_token, RawJwt};
+    use crate::sign::HS256;
+    use std::cell::RefCell;
+
+    #[test]
+    fn reports_elapsed_time() {
+        let timings = RefCell::new(Vec::new());
+        let timed = Timed::new(HS256::new(b"your-256-bit-secret").unwrap(), |elapsed| {
+            timings.borrow_mut().push(elapsed)
+        });
+
+        let token = example_token(&timed);
+        assert_eq!(timings.borrow().len(), 1);
+
+        assert!(RawJwt::decode(&token).unwrap().verify_signature(&timed));
+        assert_eq!(timings.borrow().len(), 2);
+
+        // Rejected headers never get to the signature.
+        let other = crate::sign::HS384::new(b"your-384-bit-secret").unwrap();
+        let token = example_token(&other);
+        assert!(!RawJwt::decode(&token).unwrap().verify_signature(&timed));
+        assert_eq!(timings.borrow().len(), 2);
+
+        assert!(timings.borrow().iter().all(|elapsed| !elapsed.is_zero()));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::sign::SignError;
 use crate::{
-    Algorithm, Header, JwsSigner, JwsVerifier, RecommendHeaderParams, ValidateHeaderParams,
+    Algorithm, CritHandler, Header, JwsSigner, JwsVerifier, RecommendHeaderParams,
+    SigningAlgorithm, ValidateHeaderParams,
 };
 
 macro_rules! proxy_impl {
@@ -18,14 +20,32 @@ macro_rules! proxy_impl {
             fn kid(&$self_ident) -> Option<&str> {
                 T::kid($inner_expr)
             }
+            fn typ(&$self_ident) -> Option<&str> {
+                T::typ($inner_expr)
+            }
+            fn cty(&$self_ident) -> Option<&str> {
+                T::cty($inner_expr)
+            }
         }
         impl< $( $bounded_type $(: $bound $(+ $bound_extra )*)? ),+ > JwsSigner for $target_ty
         where
             T: JwsSigner + ?Sized
         {
+            fn sign_to(&$self_ident, data: &[u8], out: &mut Vec<u8>) {
+                T::sign_to($inner_expr, data, out)
+            }
             fn sign(&$self_ident, data: &[u8]) -> Vec<u8> {
                 T::sign($inner_expr, data)
             }
+            fn signature_len(&$self_ident) -> Option<usize> {
+                T::signature_len($inner_expr)
+            }
+            fn try_sign_to(&$self_ident, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+                T::try_sign_to($inner_expr, data, out)
+            }
+            fn try_sign(&$self_ident, data: &[u8]) -> Result<Vec<u8>, SignError> {
+                T::try_sign($inner_expr, data)
+            }
         }
         impl< $( $bounded_type $(: $bound $(+ $bound_extra )*)? ),+ > ValidateHeaderParams for $target_ty
         where
@@ -34,6 +54,15 @@ macro_rules! proxy_impl {
             fn validate_header(&$self_ident, header: &Header) -> bool {
                 T::validate_header($inner_expr, header)
             }
+            fn supports_algorithm(&$self_ident, alg: SigningAlgorithm) -> bool {
+                T::supports_algorithm($inner_expr, alg)
+            }
+            fn key_id(&$self_ident) -> Option<&str> {
+                T::key_id($inner_expr)
+            }
+            fn crit_handler(&$self_ident) -> Option<&dyn CritHandler> {
+                T::crit_handler($inner_expr)
+            }
         }
         impl< $( $bounded_type $(: $bound $(+ $bound_extra )*)? ),+ > JwsVerifier for $target_ty
         where
@@ -42,6 +71,9 @@ macro_rules! proxy_impl {
             fn verify_signature(&$self_ident, data: &[u8], signature: &[u8]) -> bool {
                 T::verify_signature($inner_expr, data, signature)
             }
+            fn verify_signature_with_header(&$self_ident, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+                T::verify_signature_with_header($inner_expr, header, data, signature)
+            }
         }
     };
 }
@@ -52,4 +84,153 @@ proxy_impl!(T => std::rc::Rc<T> : self => self);
 proxy_impl!(T => std::sync::Arc<T> : self => self);
 proxy_impl!('a, T: 'a + ToOwned => std::borrow::Cow<'a, T> : self => self.as_ref());
 
-// TODO: Proxy impl for Pin<T>, I think
\ No newline at end of file
+// TODO: Proxy impl for Pin<T>, I think
+
+/// Tuples of verifiers accept a token if any of their elements do, trying them in order.
+/// Only the elements that accept the header get to verify the signature.
+macro_rules! tuple_impl {
+    ($($ty:ident . $idx:tt),+) => {
+        impl< $( $ty ),+ > ValidateHeaderParams for ( $( $ty, )+ )
+        where
+            $( $ty: ValidateHeaderParams ),+
+        {
+            fn validate_header(&self, header: &Header) -> bool {
+                $( self.$idx.validate_header(header) )||+
+            }
+            fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+                $( self.$idx.supports_algorithm(alg) )||+
+            }
+        }
+        impl< $( $ty ),+ > JwsVerifier for ( $( $ty, )+ )
+        where
+            $( $ty: JwsVerifier ),+
+        {
+            fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+                $( self.$idx.verify_signature(data, signature) )||+
+            }
+            fn verify_signature_with_header(&self, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+                $(
+                    (self.$idx.validate_header(header)
+                        && self.$idx.verify_signature_with_header(header, data, signature))
+                )||+
+            }
+        }
+    };
+}
+
+tuple_impl!(A.0, B.1);
+tuple_impl!(A.0, B.1, C.2);
+tuple_impl!(A.0, B.1, C.2, D.3);
+tuple_impl!(A.0, B.1, C.2, D.3, E.4);
+tuple_impl!(A.0, B.1, C.2, D.3, E.4, F.5);
+
+/// [`Either`](either::Either) dispatches everything to whichever side it holds, which allows
+/// choosing between two algorithms at runtime without boxing.
+#[cfg(feature = "either")]
+mod either_impl {
+    use super::*;
+    use either::Either;
+
+    impl<L, R> RecommendHeaderParams for Either<L, R>
+    where
+        L: RecommendHeaderParams,
+        R: RecommendHeaderParams,
+    {
+        fn alg(&self) -> Algorithm {
+            either::for_both!(self, inner => inner.alg())
+        }
+        fn kid(&self) -> Option<&str> {
+            either::for_both!(self, inner => inner.kid())
+        }
+        fn typ(&self) -> Option<&str> {
+            either::for_both!(self, inner => inner.typ())
+        }
+        fn cty(&self) -> Option<&str> {
+            either::for_both!(self, inner => inner.cty())
+        }
+    }
+    impl<L, R> JwsSigner for Either<L, R>
+    where
+        L: JwsSigner,
+        R: JwsSigner,
+    {
+        fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+            either::for_both!(self, inner => inner.sign_to(data, out))
+        }
+        fn sign(&self, data: &[u8]) -> Vec<u8> {
+            either::for_both!(self, inner => inner.sign(data))
+        }
+        fn signature_len(&self) -> Option<usize> {
+            either::for_both!(self, inner => inner.signature_len())
+        }
+        fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+            either::for_both!(self, inner => inner.try_sign_to(data, out))
+        }
+        fn try_sign(&self, data: &[u8]) -> Result<Vec<u8>, SignError> {
+            either::for_both!(self, inner => inner.try_sign(data))
+        }
+    }
+    impl<L, R> ValidateHeaderParams for Either<L, R>
+    where
+        L: ValidateHeaderParams,
+        R: ValidateHeaderParams,
+    {
+        fn validate_header(&self, header: &Header) -> bool {
+            either::for_both!(self, inner => inner.validate_header(header))
+        }
+        fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+            either::for_both!(self, inner => inner.supports_algorithm(alg))
+        }
+        fn key_id(&self) -> Option<&str> {
+            either::for_both!(self, inner => inner.key_id())
+        }
+        fn crit_handler(&self) -> Option<&dyn CritHandler> {
+            either::for_both!(self, inner => inner.crit_handler())
+        }
+    }
+    impl<L, R> JwsVerifier for Either<L, R>
+    where
+        L: JwsVerifier,
+        R: JwsVerifier,
+    {
+        fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+            either::for_both!(self, inner => inner.verify_signature(data, signature))
+        }
+        fn verify_signature_with_header(
+            &self,
+            header: &Header,
+            data: &[u8],
+            signature: &[u8],
+        ) -> bool {
+            either::for_both!(self, inner => {
+                inner.verify_signature_with_header(header, data, signature)
+            })
+        }
+    }
+
+    #[cfg(all(test, feature = "hmac-sha2"))]
+    mod tests {
+        use super::*;
+        use crate::jwt::{example_token, RawJwt};
+        use crate::sign::{HS256, HS512};
+
+        #[test]
+        fn either_signer() {
+            let signers: [Either<HS256, HS512>; 2] = [
+                Either::Left(HS256::new(b"left").unwrap()),
+                Either::Right(HS512::new(b"right").unwrap()),
+            ];
+            let algorithms = [SigningAlgorithm::HS256, SigningAlgorithm::HS512];
+            for (signer, alg) in signers.iter().zip(algorithms) {
+                assert_eq!(signer.alg(), Algorithm::Signing(alg));
+                let token = example_token(signer);
+                let jwt = RawJwt::decode(&token).unwrap();
+                assert_eq!(jwt.header.algorithm, Algorithm::Signing(alg));
+                assert!(jwt.verify_signature(signer));
+                for other in signers.iter().filter(|other| !std::ptr::eq(*other, signer)) {
+                    assert!(!jwt.verify_signature(other));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{Header, JwsVerifier, SigningAlgorithm, ValidateHeaderParams};
+use std::collections::HashMap;
+
+/// A verifier that dispatches to a different verifier for each key ID.
+///
+/// The verifier for a token is looked up using the `kid` of its header, so tokens without a
+/// `kid`, or with one that isn't registered, are rejected. The verifier that is found still
+/// checks the rest of the header, such as the algorithm.
+///
+/// This is usually built from a `jwk::JwkSet` (which needs the `jwk` feature) using
+/// [`collect`](Iterator::collect):
+///
+/// ```
+/// # #[cfg(all(feature = "jwk", feature = "ecdsa"))] {
+/// use jwt2::jwk::JwkSet;
+/// use jwt2::util::VerifierRegistry;
+///
+/// let jwks: JwkSet = serde_json::from_str(r#"{ "keys": [] }"#).unwrap();
+/// let registry: VerifierRegistry = jwks
+///     .keys
+///     .iter()
+///     .filter_map(|jwk| Some((jwk.key_id.clone()?, jwk.to_es256_public().ok()?)))
+///     .collect();
+/// assert!(registry.is_empty());
+/// # }
+/// ```
+#[derive(Default)]
+pub struct VerifierRegistry {
+    verifiers: HashMap<String, Box<dyn JwsVerifier + Send + Sync>>,
+}
+impl VerifierRegistry {
+    /// Creates an empty registry, which rejects everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Uses `verifier` for tokens with the key ID `kid`, returning the verifier previously used
+    /// for it, if any.
+    pub fn insert<Verifier>(
+        &mut self,
+        kid: impl Into<String>,
+        verifier: Verifier,
+    ) -> Option<Box<dyn JwsVerifier + Send + Sync>>
+    where
+        Verifier: JwsVerifier + Send + Sync + 'static,
+    {
+        self.verifiers.insert(kid.into(), Box::new(verifier))
+    }
+    /// Like [`VerifierRegistry::insert`], but for chaining.
+    pub fn with<Verifier>(mut self, kid: impl Into<String>, verifier: Verifier) -> Self
+    where
+        Verifier: JwsVerifier + Send + Sync + 'static,
+    {
+        self.insert(kid, verifier);
+        self
+    }
+    /// Stops accepting tokens with the key ID `kid`, returning the verifier that was used for
+    /// it.
+    pub fn remove(&mut self, kid: &str) -> Option<Box<dyn JwsVerifier + Send + Sync>> {
+        self.verifiers.remove(kid)
+    }
+    /// Gets the verifier used for tokens with the key ID `kid`.
+    pub fn get(&self, kid: &str) -> Option<&(dyn JwsVerifier + Send + Sync)> {
+        self.verifiers.get(kid).map(Box::as_ref)
+    }
+    pub fn len(&self) -> usize {
+        self.verifiers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.verifiers.is_empty()
+    }
+
+    fn for_header(&self, header: &Header) -> Option<&(dyn JwsVerifier + Send + Sync)> {
+        header.key_id.as_deref().and_then(|kid| self.get(kid))
+    }
+}
+
+impl<Kid, Verifier> FromIterator<(Kid, Verifier)> for VerifierRegistry
+where
+    Kid: Into<String>,
+    Verifier: JwsVerifier + Send + Sync + 'static,
+{
+    fn from_iter<I: IntoIterator<Item = (Kid, Verifier)>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        registry.extend(iter);
+        registry
+    }
+}
+impl<Kid, Verifier> Extend<(Kid, Verifier)> for VerifierRegistry
+where
+    Kid: Into<String>,
+    Verifier: JwsVerifier + Send + Sync + 'static,
+{
+    fn extend<I: IntoIterator<Item = (Kid, Verifier)>>(&mut self, iter: I) {
+        for (kid, verifier) in iter {
+            self.insert(kid, verifier);
+        }
+    }
+}
+
+impl ValidateHeaderParams for VerifierRegistry {
+    fn validate_header(&self, header: &Header) -> bool {
+        self.for_header(header)
+            .is_some_and(|verifier| verifier.validate_header(header))
+    }
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        self.verifiers
+            .values()
+            .any(|verifier| verifier.supports_algorithm(alg))
+    }
+}
+impl JwsVerifier for VerifierRegistry {
+    /// Without a header, there's no key ID to look up, so this tries every verifier.
+    fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+        self.verifiers
+            .values()
+            .any(|verifier| verifier.verify_signature(data, signature))
+    }
+    fn verify_signature_with_header(&self, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+        self.for_header(header)
+            .is_some_and(|verifier| verifier.verify_signature_with_header(header, data, signature))
+    }
+}
+
+#[cfg(all(test, feature = "jwk", feature = "ecdsa", feature = "rand"))]
+mod tests {
+    use super::*;
+    use crate::jwk::JwkSet;
+    use crate::jwt::example_token;
+    use crate::jwt::{RawJwt, VerifyError};
+    use crate::sign::ecdsa::{ES256, ES384};
+    use crate::util::WithKeyId;
+    use crate::JwsSigner;
+
+    #[test]
+    fn collect_from_jwk_set() {
+        let key_1 = WithKeyId::new("key-1".to_string(), ES256::generate());
+        let key_2 = WithKeyId::new("key-2".to_string(), ES256::generate());
+        let key_3 = WithKeyId::new("key-3".to_string(), ES384::generate());
+        let mut jwks = JwkSet::new();
+        for (kid, mut jwk) in [
+            (key_1.key_id(), key_1.to_jwk()),
+            (key_2.key_id(), key_2.to_jwk()),
+            (key_3.key_id(), key_3.to_jwk()),
+        ] {
+            jwk.key_id = Some(kid.to_string());
+            jwks.add(jwk);
+        }
+
+        let registry: VerifierRegistry = jwks
+            .keys
+            .iter()
+            .map(|jwk| {
+                let verifier: Box<dyn JwsVerifier + Send + Sync> = match jwk.algorithm.as_deref() {
+                    Some("ES256") => Box::new(jwk.to_es256_public().unwrap()),
+                    Some("ES384") => Box::new(jwk.to_es384_public().unwrap()),
+                    alg => panic!("unexpected algorithm {alg:?}"),
+                };
+                (jwk.key_id.clone().unwrap(), verifier)
+            })
+            .collect();
+        assert_eq!(registry.len(), 3);
+
+        for signer in [&key_1 as &dyn JwsSigner, &key_2, &key_3] {
+            assert!(RawJwt::decode(&example_token(signer))
+                .unwrap()
+                .verify_signature(&registry));
+        }
+
+        // The key ID picks the key, so a token claiming to be signed by another key fails.
+        let impostor = WithKeyId::new("key-2".to_string(), key_1.inner.clone());
+        assert!(matches!(
+            RawJwt::decode(&example_token(&impostor))
+                .unwrap()
+                .verify_signature_detailed(&registry),
+            Err(VerifyError::SignatureInvalid)
+        ));
+        let unknown = WithKeyId::new("key-4".to_string(), key_1.inner.clone());
+        assert!(matches!(
+            RawJwt::decode(&example_token(&unknown))
+                .unwrap()
+                .verify_signature_detailed(&registry),
+            Err(VerifyError::HeaderRejected)
+        ));
+        assert!(!RawJwt::decode(&example_token(&key_1.inner))
+            .unwrap()
+            .verify_signature(&registry));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::sign::SignError;
+use crate::{
+    Algorithm, CritHandler, Header, JwsSigner, JwsVerifier, RecommendHeaderParams,
+    SigningAlgorithm, ValidateHeaderParams,
+};
+
+/// Attaches a [`CritHandler`] to a verifier, so that tokens with the critical extensions it
+/// handles can be verified.
+///
+/// Everything else is forwarded to `inner`.
+#[derive(Clone)]
+pub struct WithCritHandler<Inner, Handler> {
+    pub inner: Inner,
+    pub handler: Handler,
+}
+impl<Inner, Handler> WithCritHandler<Inner, Handler> {
+    pub fn new(inner: Inner, handler: Handler) -> Self {
+        Self { inner, handler }
+    }
+}
+
+impl<Inner, Handler> RecommendHeaderParams for WithCritHandler<Inner, Handler>
+where
+    Inner: RecommendHeaderParams,
+{
+    fn alg(&self) -> Algorithm {
+        self.inner.alg()
+    }
+    fn kid(&self) -> Option<&str> {
+        self.inner.kid()
+    }
+    fn typ(&self) -> Option<&str> {
+        self.inner.typ()
+    }
+    fn cty(&self) -> Option<&str> {
+        self.inner.cty()
+    }
+}
+
+impl<Inner, Handler> JwsSigner for WithCritHandler<Inner, Handler>
+where
+    Inner: JwsSigner,
+{
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        self.inner.sign_to(data, out)
+    }
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        self.inner.sign(data)
+    }
+    fn signature_len(&self) -> Option<usize> {
+        self.inner.signature_len()
+    }
+    fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+        self.inner.try_sign_to(data, out)
+    }
+    fn try_sign(&self, data: &[u8]) -> Result<Vec<u8>, SignError> {
+        self.inner.try_sign(data)
+    }
+}
+
+impl<Inner, Handler> ValidateHeaderParams for WithCritHandler<Inner, Handler>
+where
+    Inner: ValidateHeaderParams,
+    Handler: CritHandler,
+{
+    fn validate_header(&self, header: &Header) -> bool {
+        self.inner.validate_header(header)
+    }
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        self.inner.supports_algorithm(alg)
+    }
+    fn key_id(&self) -> Option<&str> {
+        self.inner.key_id()
+    }
+    fn crit_handler(&self) -> Option<&dyn CritHandler> {
+        Some(&self.handler)
+    }
+}
+
+impl<Inner, Handler> JwsVerifier for WithCritHandler<Inner, Handler>
+where
+    Inner: JwsVerifier,
+    Handler: CritHandler,
+{
+    fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+        self.inner.verify_signature(data, signature)
+    }
+    fn verify_signature_with_header(&self, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+        self.inner
+            .verify_signature_with_header(header, data, signature)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::sign::SignError;
+use crate::{
+    Algorithm, CritHandler, Header, JwsSigner, JwsVerifier, RecommendHeaderParams,
+    SigningAlgorithm, ValidateHeaderParams,
+};
+
+/// Recommends fixed values for the `kid`, `typ` and `cty` header parameters, so headers
+/// created with [`Header::recommended`] carry them without setting them on every token.
+///
+/// Parameters that aren't set here are left to `inner`. Everything else, including
+/// verification, is forwarded to `inner` as-is; to also check the `kid` when verifying, see
+/// [`WithKeyId`](crate::util::WithKeyId).
+///
+/// ```
+/// # #[cfg(feature = "hmac-sha2")] {
+/// use jwt2::sign::HS256;
+/// use jwt2::util::WithHeaderDefaults;
+/// use jwt2::Header;
+///
+/// let signer = WithHeaderDefaults::new(HS256::new(b"your-256-bit-secret").unwrap())
+///     .with_key_id("key-1")
+///     .with_obj_type("at+jwt");
+/// let header = Header::recommended(&signer);
+/// assert_eq!(header.key_id.as_deref(), Some("key-1"));
+/// assert_eq!(header.obj_type.as_deref(), Some("at+jwt"));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct WithHeaderDefaults<Inner> {
+    pub inner: Inner,
+    pub key_id: Option<String>,
+    pub obj_type: Option<String>,
+    pub content_type: Option<String>,
+}
+impl<Inner> WithHeaderDefaults<Inner> {
+    /// Creates a new [`WithHeaderDefaults`] without any defaults of its own.
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            key_id: None,
+            obj_type: None,
+            content_type: None,
+        }
+    }
+    /// Sets the recommended `kid`.
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+    /// Sets the recommended `typ`.
+    pub fn with_obj_type(mut self, obj_type: impl Into<String>) -> Self {
+        self.obj_type = Some(obj_type.into());
+        self
+    }
+    /// Sets the recommended `cty`.
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+}
+
+impl<Inner> RecommendHeaderParams for WithHeaderDefaults<Inner>
+where
+    Inner: RecommendHeaderParams,
+{
+    fn alg(&self) -> Algorithm {
+        self.inner.alg()
+    }
+    fn kid(&self) -> Option<&str> {
+        self.key_id.as_deref().or_else(|| self.inner.kid())
+    }
+    fn typ(&self) -> Option<&str> {
+        self.obj_type.as_deref().or_else(|| self.inner.typ())
+    }
+    fn cty(&self) -> Option<&str> {
+        self.content_type.as_deref().or_else(|| self.inner.cty())
+    }
+}
+
+impl<Inner> JwsSigner for WithHeaderDefaults<Inner>
+where
+    Inner: JwsSigner,
+{
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        self.inner.sign_to(data, out)
+    }
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        self.inner.sign(data)
+    }
+    fn signature_len(&self) -> Option<usize> {
+        self.inner.signature_len()
+    }
+    fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+        self.inner.try_sign_to(data, out)
+    }
+    fn try_sign(&self, data: &[u8]) -> Result<Vec<u8>, SignError> {
+        self.inner.try_sign(data)
+    }
+}
+
+impl<Inner> ValidateHeaderParams for WithHeaderDefaults<Inner>
+where
+    Inner: ValidateHeaderParams,
+{
+    fn validate_header(&self, header: &Header) -> bool {
+        self.inner.validate_header(header)
+    }
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        self.inner.supports_algorithm(alg)
+    }
+    fn key_id(&self) -> Option<&str> {
+        self.inner.key_id()
+    }
+    fn crit_handler(&self) -> Option<&dyn CritHandler> {
+        self.inner.crit_handler()
+    }
+}
+
+impl<Inner> JwsVerifier for WithHeaderDefaults<Inner>
+where
+    Inner: JwsVerifier,
+{
+    fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
+        self.inner.verify_signature(data, signature)
+    }
+    fn verify_signature_with_header(&self, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+        self.inner
+            .verify_signature_with_header(header, data, signature)
+    }
+}
+
+#[cfg(all(test, feature = "hmac-sha2"))]
+mod tests {
+    use super::*;
+    use crate::jwt::{example_token, RawJwt};
+    use crate::sign::HS256;
+    use crate::util::WithKeyId;
+
+    #[test]
+    fn stamps_header() {
+        let signer = WithHeaderDefaults::new(WithKeyId::new(
+            "inner".to_string(),
+            HS256::new(b"your-256-bit-secret").unwrap(),
+        ))
+        .with_obj_type("JWT")
+        .with_content_type("example");
+
+        let token = example_token(&signer);
+        let jwt = RawJwt::decode(&token).unwrap();
+        assert_eq!(jwt.header.obj_type.as_deref(), Some("JWT"));
+        assert_eq!(jwt.header.content_type.as_deref(), Some("example"));
+        // Unset defaults are left to the inner signer.
+        assert_eq!(jwt.header.key_id.as_deref(), Some("inner"));
+        assert!(jwt.verify_signature(&signer));
+
+        let signer = signer.with_key_id("outer");
+        assert_eq!(
+            Header::recommended(&signer).key_id.as_deref(),
+            Some("outer")
+        );
+    }
+}
@@ -1,14 +1,18 @@
+use crate::sign::SignError;
 use crate::{
-    Algorithm, Header, JwsSigner, JwsVerifier, RecommendHeaderParams, ValidateHeaderParams,
+    Algorithm, CritHandler, Header, JwsSigner, JwsVerifier, RecommendHeaderParams,
+    SigningAlgorithm, ValidateHeaderParams,
 };
 
 /// A utility for use with [`Header::key_id`].
 ///
 /// In the case of [`ValidateHeaderParams`]s, [`ValidateHeaderParams::validate_header`] will also
 /// make sure that the `key_id` if present, is equal to the specified `key_id`.
-/// If the `key_id` isn't present, it returns whatever the `accept_missing_key_id` field is set to.
+/// If the `key_id` isn't present, it rejects the header unless the `accept_missing_key_id` field
+/// is set, in which case the inner validator decides.
 ///
 /// See [`WithKeyId::new`] and [`WithKeyId::new_accept_missing`].
+#[derive(Clone)]
 pub struct WithKeyId<Inner> {
     pub key_id: String,
     pub inner: Inner,
@@ -43,6 +47,22 @@ impl<Inner> WithKeyId<Inner> {
     }
 }
 
+/// Allows calling methods of `Inner` through the wrapper, e.g. `RS256::public`.
+///
+/// Methods of the signing and verifying traits still go through [`WithKeyId`] itself.
+impl<Inner> std::ops::Deref for WithKeyId<Inner> {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        &self.inner
+    }
+}
+impl<Inner> AsRef<Inner> for WithKeyId<Inner> {
+    fn as_ref(&self) -> &Inner {
+        &self.inner
+    }
+}
+
 impl<Inner> RecommendHeaderParams for WithKeyId<Inner>
 where
     Inner: RecommendHeaderParams,
@@ -53,15 +73,33 @@ where
     fn kid(&self) -> Option<&str> {
         Some(self.key_id.as_str())
     }
+    fn typ(&self) -> Option<&str> {
+        self.inner.typ()
+    }
+    fn cty(&self) -> Option<&str> {
+        self.inner.cty()
+    }
 }
 
 impl<Inner> JwsSigner for WithKeyId<Inner>
 where
     Inner: JwsSigner,
 {
+    fn sign_to(&self, data: &[u8], out: &mut Vec<u8>) {
+        self.inner.sign_to(data, out)
+    }
     fn sign(&self, data: &[u8]) -> Vec<u8> {
         self.inner.sign(data)
     }
+    fn signature_len(&self) -> Option<usize> {
+        self.inner.signature_len()
+    }
+    fn try_sign_to(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), SignError> {
+        self.inner.try_sign_to(data, out)
+    }
+    fn try_sign(&self, data: &[u8]) -> Result<Vec<u8>, SignError> {
+        self.inner.try_sign(data)
+    }
 }
 
 impl<Inner> ValidateHeaderParams for WithKeyId<Inner>
@@ -70,7 +108,7 @@ where
 {
     fn validate_header(&self, header: &Header) -> bool {
         let Some(ref header_key_id) = header.key_id else {
-            return self.accept_missing_key_id;
+            return self.accept_missing_key_id && self.inner.validate_header(header);
         };
 
         if header_key_id.eq(&self.key_id) {
@@ -79,6 +117,15 @@ where
             false
         }
     }
+    fn supports_algorithm(&self, alg: SigningAlgorithm) -> bool {
+        self.inner.supports_algorithm(alg)
+    }
+    fn key_id(&self) -> Option<&str> {
+        Some(self.key_id.as_str())
+    }
+    fn crit_handler(&self) -> Option<&dyn CritHandler> {
+        self.inner.crit_handler()
+    }
 }
 
 impl<Inner> JwsVerifier for WithKeyId<Inner>
@@ -88,4 +135,53 @@ where
     fn verify_signature(&self, data: &[u8], signature: &[u8]) -> bool {
         self.inner.verify_signature(data, signature)
     }
+    fn verify_signature_with_header(&self, header: &Header, data: &[u8], signature: &[u8]) -> bool {
+        self.inner
+            .verify_signature_with_header(header, data, signature)
+    }
+}
+
+#[cfg(all(test, feature = "hmac-sha2"))]
+mod tests {
+    use super::*;
+    use crate::sign::HS256;
+
+    #[test]
+    fn missing_key_id_delegates() {
+        let accepting = WithKeyId::new_accept_missing(
+            "key-1".to_string(),
+            HS256::new(b"your-256-bit-secret").unwrap(),
+        );
+        let strict = WithKeyId::new("key-1".to_string(), accepting.inner.clone());
+
+        let header = Header::new(Algorithm::Signing(SigningAlgorithm::HS256));
+        assert!(accepting.validate_header(&header));
+        assert!(!strict.validate_header(&header));
+
+        // A missing kid must not let a mismatched algorithm through.
+        let header = Header::new(Algorithm::Signing(SigningAlgorithm::HS384));
+        assert!(!accepting.validate_header(&header));
+        assert!(!accepting.validate_header(&Header::new(Algorithm::None)));
+
+        let header = header.with_key_id("key-1");
+        assert!(!accepting.validate_header(&header));
+        let header = header.with_algorithm(Algorithm::Signing(SigningAlgorithm::HS256));
+        assert!(accepting.validate_header(&header));
+        assert!(strict.validate_header(&header));
+    }
+
+    #[test]
+    #[cfg(feature = "ecdsa")]
+    fn deref_to_inner() {
+        use crate::sign::ecdsa::ES256;
+
+        let key = p256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap();
+        let signer = WithKeyId::new("key-1".to_string(), ES256::from(key));
+        // `public` is a method of `ES256`, not of the wrapper.
+        let public = signer.public();
+        assert!(public.verify_signature(b"hello", &signer.sign(b"hello")));
+        assert_eq!(signer.as_ref().public_key_bytes(), public.public_key_bytes());
+        // The wrapper's own methods still take precedence.
+        assert_eq!(RecommendHeaderParams::kid(&signer), Some("key-1"));
+    }
 }